@@ -1,26 +1,196 @@
 #![allow(dead_code)]
 
 pub mod animation_util;
+mod compare;
 mod coordinate;
+mod error;
+mod indexed;
+mod nearest;
+mod peek_mut;
+mod range;
+mod sorted;
 
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Bound, Index, RangeBounds},
+};
 
+pub use compare::*;
 pub use coordinate::*;
+pub use error::{BeapError, ChildSide, CoordinateError, HeapViolation};
+pub use indexed::*;
+pub use nearest::TieBreak;
+pub use peek_mut::PeekMut;
+pub use range::Range;
+pub use sorted::{DrainSorted, IntoIterSorted};
 
-use self::animation_util::{AnimatedSearch, AnimatedSwap, IgnoreSteps, TrackSteps};
+use self::{
+    animation_util::{AnimatedSearch, AnimatedSwap, IgnoreSteps, TrackSteps},
+    range::ColumnSpan,
+};
 
-#[derive(Debug, Clone)]
-pub struct Beap<T: Ord> {
+// A beap ordered by `C`. The element that compares as the smallest is always at the top,
+// so "smaller" and "greater" in the functions below refer to the beap's ordering.
+#[derive(Clone)]
+pub struct Beap<T, C = MinOrder> {
     data: Vec<T>,
+    compare: C,
+}
+
+// A beap that keeps the largest element at the top
+pub type MaxBeap<T> = Beap<T, MaxOrder>;
+
+impl<T: fmt::Debug, C> fmt::Debug for Beap<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Beap").field("data", &self.data).finish()
+    }
+}
+
+impl<T, C: Default> Default for Beap<T, C> {
+    fn default() -> Self {
+        Self {
+            data: Vec::new(),
+            compare: C::default(),
+        }
+    }
 }
 
 impl<T: Ord> Beap<T> {
     pub fn new() -> Self {
-        Self { data: Vec::new() }
+        Self::new_by(MinOrder)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_by(capacity, MinOrder)
+    }
+
+    // Create an empty beap with exactly enough space to fill the given number of rows
+    pub fn with_depth(rows: usize) -> Self {
+        Self::with_depth_by(rows, MinOrder)
+    }
+
+    // Build a beap out of all the items in a vector at once, in O(N log N)
+    pub fn from_vec(data: Vec<T>) -> Self {
+        Self::from_vec_by(data, MinOrder)
+    }
+    pub fn from_vec_steps<S: TrackSteps<AnimatedSwap>>(data: Vec<T>) -> S::WrapOutput<Self> {
+        Self::from_vec_by_steps::<S>(data, MinOrder)
+    }
+
+    // Load a beap from a vector that is already laid out as one, e.g. from into_vec.
+    // Fails if the layout doesn't have the heap property.
+    pub fn try_from_vec(data: Vec<T>) -> Result<Self, HeapViolation> {
+        Self::try_from_vec_by(data, MinOrder)
+    }
+
+    // Same as try_from_vec, but trusts the layout without checking it in release builds
+    pub fn from_vec_unchecked(data: Vec<T>) -> Self {
+        Self::from_vec_unchecked_by(data, MinOrder)
+    }
+
+    // Pop the smallest item off the heap. Same as pop_top, as a min beap keeps the smallest at the top.
+    pub fn pop_smallest(&mut self) -> Option<T> {
+        self.pop_top()
+    }
+    pub fn pop_smallest_steps<S: TrackSteps<AnimatedSwap>>(&mut self) -> S::WrapOutput<Option<T>> {
+        self.pop_top_steps::<S>()
+    }
+}
+
+impl<T, C> Beap<T, C> {
+    // Check that there is an item at a coordinate, so that its array index can be used directly
+    fn check_coord(&self, coord: BeapCoordinate) -> Result<(), BeapError> {
+        // Catches the coordinates whose array index would overflow
+        BeapCoordinate::try_from((coord.row(), coord.pos()))?;
+
+        if coord.array_index() >= self.data.len() {
+            return Err(BeapError::CoordinateOutOfBounds {
+                coord,
+                len: self.data.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T, C: Compare<T>> Beap<T, C> {
+    // Create an empty beap ordered by a custom ordering, e.g. MaxOrder or a comparison closure
+    pub fn new_by(compare: C) -> Self {
+        Self {
+            data: Vec::new(),
+            compare,
+        }
+    }
+
+    pub fn with_capacity_by(capacity: usize, compare: C) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            compare,
+        }
+    }
+
+    // Create an empty beap ordered by a custom ordering, with exactly enough space to fill the
+    // given number of rows
+    pub fn with_depth_by(rows: usize, compare: C) -> Self {
+        // The first index of the row after the last one is the number of coordinates above it.
+        // Panics on overflow, same as Vec::with_capacity does for a capacity that can't exist.
+        let capacity = BeapCoordinate::checked_row_start_index(rows).expect("capacity overflow");
+        Self::with_capacity_by(capacity, compare)
+    }
+
+    // Build a beap ordered by a custom ordering out of all the items in a vector at once.
+    // A sorted array is already laid out as a beap, as every child comes after its parents, so this
+    // just sorts the items in O(N log N), which no other way of building the layout can beat.
+    pub fn from_vec_by(mut data: Vec<T>, compare: C) -> Self {
+        data.sort_by(|a, b| compare.compare(a, b));
+        let beap = Self { data, compare };
+        beap.debug_validate();
+        beap
+    }
+    // Sorting doesn't give swaps to animate, so this builds the beap with beapify instead, which is
+    // O(N sqrt N) but still about half the swaps of inserting the items one by one
+    pub fn from_vec_by_steps<S: TrackSteps<AnimatedSwap>>(
+        data: Vec<T>,
+        compare: C,
+    ) -> S::WrapOutput<Self> {
+        let mut steps = S::new();
+
+        let mut beap = Self { data, compare };
+        beap.beapify(&mut steps);
+        beap.debug_validate();
+
+        steps.wrap_output(beap)
+    }
+
+    // Load a beap ordered by a custom ordering from a vector that is already laid out as one
+    pub fn try_from_vec_by(data: Vec<T>, compare: C) -> Result<Self, HeapViolation> {
+        let beap = Self { data, compare };
+        beap.validate()?;
+        Ok(beap)
+    }
+
+    // Same as try_from_vec_by, but trusts the layout without checking it, unless the debug-validate
+    // feature is on.
+    // A layout without the heap property can't cause undefined behaviour, only wrong results,
+    // so this doesn't need to be unsafe.
+    pub fn from_vec_unchecked_by(data: Vec<T>, compare: C) -> Self {
+        let beap = Self { data, compare };
+        beap.debug_validate();
+        beap
+    }
+
+    fn order(&self, a: &T, b: &T) -> Ordering {
+        self.compare.compare(a, b)
     }
 
-    // The function to run the bubble up algorithm, while tracking the action it took at each step
-    fn bubble_up<S: TrackSteps<AnimatedSwap>>(&mut self, steps: &mut S, mut coord: BeapCoordinate) {
+    // The function to run the bubble up algorithm, while tracking the action it took at each step.
+    // Returns the coordinate the item ended up at.
+    fn bubble_up<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        steps: &mut S,
+        mut coord: BeapCoordinate,
+    ) -> BeapCoordinate {
         loop {
             if coord == BeapCoordinate::zero() {
                 // We've reached the top. There is no where else to swap.
@@ -35,7 +205,10 @@ impl<T: Ord> Beap<T> {
             let greater = if let Some(left_parent) = left_parent {
                 if let Some(right_parent) = right_parent {
                     // If both indexes are present, return the larger one
-                    if self.data[left_parent.array_index()] > self.data[right_parent.array_index()]
+                    if self.order(
+                        &self.data[left_parent.array_index()],
+                        &self.data[right_parent.array_index()],
+                    ) == Ordering::Greater
                     {
                         left_parent
                     } else {
@@ -44,20 +217,18 @@ impl<T: Ord> Beap<T> {
                 } else {
                     left_parent
                 }
+            } else if let Some(right_parent) = right_parent {
+                right_parent
             } else {
-                if let Some(right_parent) = right_parent {
-                    right_parent
-                } else {
-                    // This can only be reached if the coordinate is zero
-                    break;
-                }
+                // This can only be reached if the coordinate is zero
+                break;
             };
 
             let smaller_index = greater.array_index();
             let index = coord.array_index();
 
             // Swap if the parent is greater. Otherwise, break.
-            if self.data[index] < self.data[smaller_index] {
+            if self.order(&self.data[index], &self.data[smaller_index]) == Ordering::Less {
                 steps.add_step(AnimatedSwap {
                     first: coord,
                     second: greater,
@@ -69,10 +240,17 @@ impl<T: Ord> Beap<T> {
                 break;
             }
         }
+
+        coord
     }
 
-    // The function to run the sink algorithm, while tracking the action it took at each step
-    fn sink<S: TrackSteps<AnimatedSwap>>(&mut self, steps: &mut S, mut coord: BeapCoordinate) {
+    // The function to run the sink algorithm, while tracking the action it took at each step.
+    // Returns the coordinate the item ended up at.
+    fn sink<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        steps: &mut S,
+        mut coord: BeapCoordinate,
+    ) -> BeapCoordinate {
         loop {
             let left_child = coord.left_child();
             let right_child = coord.right_child();
@@ -83,19 +261,22 @@ impl<T: Ord> Beap<T> {
             }
 
             // If right is out of bounds, use left child. Otherwise pick the smaller child.
-            let smaller = if right_child.array_index() >= self.data.len() {
-                left_child
-            } else if self.data[left_child.array_index()] < self.data[right_child.array_index()] {
-                left_child
-            } else {
+            let smaller = if right_child.array_index() < self.data.len()
+                && self.order(
+                    &self.data[right_child.array_index()],
+                    &self.data[left_child.array_index()],
+                ) != Ordering::Greater
+            {
                 right_child
+            } else {
+                left_child
             };
 
             let smaller_index = smaller.array_index();
             let index = coord.array_index();
 
             // If the child is smaller, swap to it.
-            if self.data[index] > self.data[smaller_index] {
+            if self.order(&self.data[index], &self.data[smaller_index]) == Ordering::Greater {
                 steps.add_step(AnimatedSwap {
                     first: coord,
                     second: smaller,
@@ -107,6 +288,163 @@ impl<T: Ord> Beap<T> {
                 break;
             }
         }
+
+        coord
+    }
+
+    // Move the item at a coordinate up or down, whichever it needs, to preserve heap property.
+    // Returns the coordinate the item ended up at.
+    fn restore<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        steps: &mut S,
+        coord: BeapCoordinate,
+    ) -> BeapCoordinate {
+        let moved = self.bubble_up(steps, coord);
+        if moved != coord {
+            moved
+        } else {
+            self.sink(steps, coord)
+        }
+    }
+
+    // Restore the heap property of the whole array, by sinking every item that has children,
+    // starting from the last row with children and going upwards. By the time an item is sunk,
+    // everything below it is already in order, so it only has to move down.
+    // In the worst case this does about half of the swaps that inserting the items one by one would.
+    fn beapify<S: TrackSteps<AnimatedSwap>>(&mut self, steps: &mut S) {
+        for row in (0..self.depth().saturating_sub(1)).rev() {
+            for pos in 0..=row {
+                self.sink(steps, BeapCoordinate::new(row, pos));
+            }
+        }
+    }
+
+    // Restore the heap property after items were pushed onto the end of a valid beap of old_len items.
+    // Bubbling up each new item costs O(sqrt N) per item, while beapify costs about O(N sqrt N) in
+    // total, so rebuilding is only cheaper once the new items make up about a third of the beap.
+    fn restore_appended<S: TrackSteps<AnimatedSwap>>(&mut self, steps: &mut S, old_len: usize) {
+        let added = self.data.len() - old_len;
+
+        if added * 2 >= old_len {
+            self.beapify(steps);
+        } else {
+            // Same as inserting them one by one, as bubbling up never looks past the item itself
+            for index in old_len..self.data.len() {
+                self.bubble_up(steps, BeapCoordinate::from_index(index));
+            }
+        }
+    }
+
+    // Remove every item matching the predicate, then restore the heap property in a single pass
+    fn remove_where<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        steps: &mut S,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> Vec<T> {
+        let mut removed = Vec::new();
+
+        // Go backwards so that the last item, which gets swapped into each gap, was already checked
+        for index in (0..self.data.len()).rev() {
+            if predicate(&self.data[index]) {
+                removed.push(self.data.swap_remove(index));
+                // Add the animation step for the line above
+                steps.add_step(AnimatedSwap {
+                    first: BeapCoordinate::from_index(self.data.len()),
+                    second: BeapCoordinate::from_index(index),
+                    overwrite: true,
+                });
+            }
+        }
+
+        if !removed.is_empty() {
+            self.beapify(steps);
+        }
+
+        removed
+    }
+
+    // Replace the value at a coordinate that is known to be in bounds, then move it to preserve heap property
+    fn replace_at<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        steps: &mut S,
+        coord: BeapCoordinate,
+        value: T,
+    ) -> T {
+        // Compare the value with the new value
+        let diff = self.order(&self.data[coord.array_index()], &value);
+
+        let old = std::mem::replace(&mut self.data[coord.array_index()], value);
+
+        // Based on the comparison, run the appropriate algorithm
+        match diff {
+            Ordering::Greater => {
+                self.bubble_up(steps, coord);
+            }
+            Ordering::Less => {
+                self.sink(steps, coord);
+            }
+            Ordering::Equal => {}
+        }
+
+        old
+    }
+
+    // Remove the value at a coordinate that is known to be in bounds, filling the gap with the last value
+    fn remove_at<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        steps: &mut S,
+        coord: BeapCoordinate,
+    ) -> T {
+        // swap_remove swaps the last element with the one at the given index and returns it
+        let removed = self.data.swap_remove(coord.array_index());
+        // Add the animation step for the line above
+        steps.add_step(AnimatedSwap {
+            first: BeapCoordinate::from_index(self.data.len()),
+            second: coord,
+            overwrite: true,
+        });
+
+        // The last element came from anywhere in the last row, so unless the gap was the last
+        // coordinate, it can be larger than its new children or smaller than its new parents
+        if coord.array_index() < self.data.len() {
+            self.restore(steps, coord);
+        }
+
+        removed
+    }
+
+    // Find the coordinate of the largest item. Any item with a child is smaller than that child,
+    // so the largest item must be one of the items without children, which are the last row and
+    // the end of the row before it. That's O(sqrt N) items to check.
+    fn largest_coord(&self) -> Option<BeapCoordinate> {
+        let last = BeapCoordinate::from_index(self.data.len().checked_sub(1)?);
+
+        let last_row = (0..=last.pos()).map(|pos| BeapCoordinate::new(last.row(), pos));
+        let row_before =
+            (last.pos() + 1..last.row()).map(|pos| BeapCoordinate::new(last.row() - 1, pos));
+
+        last_row.chain(row_before).reduce(|largest, coord| {
+            let compared = self.order(
+                &self.data[coord.array_index()],
+                &self.data[largest.array_index()],
+            );
+            if compared == Ordering::Greater {
+                coord
+            } else {
+                largest
+            }
+        })
+    }
+
+    // Check the heap property after every operation to catch any that breaks it straight away.
+    // This is O(N), so it's only on in this crate's tests or with the debug-validate feature,
+    // otherwise it would make every O(sqrt N) operation O(N) in all debug builds.
+    fn debug_validate(&self) {
+        if cfg!(any(test, feature = "debug-validate")) {
+            if let Err(violation) = self.validate() {
+                panic!("{}", violation);
+            }
+        }
     }
 
     // Step through the beap, starting in the bottom left corner, based on the compare function.
@@ -116,15 +454,12 @@ impl<T: Ord> Beap<T> {
         steps: &mut S,
         mut compare: impl FnMut(BeapCoordinate, &'a T) -> Ordering,
     ) -> Option<BeapCoordinate> {
-        // Start at the bottom left of the beap
-        let mut coord = BeapCoordinate::new(self.depth() - 1, 0);
+        // Start at the bottom left of the beap, if there is anything to search through
+        let mut coord = BeapCoordinate::new(self.depth().checked_sub(1)?, 0);
 
         loop {
             // If the value at the coordinate is out of bounds of the array, then we've reached the end
-            let value = match self.data.get(coord.array_index()) {
-                Some(value) => value,
-                None => return None,
-            };
+            let value = self.data.get(coord.array_index())?;
 
             steps.add_step(AnimatedSearch { coord });
 
@@ -132,10 +467,8 @@ impl<T: Ord> Beap<T> {
             let mut compared = compare(coord, value);
 
             // If the comparison is lesser but going down isn't possible then try going up instead
-            if compared == Ordering::Less {
-                if coord.right_child().array_index() >= self.data.len() {
-                    compared = Ordering::Greater;
-                }
+            if compared == Ordering::Less && coord.right_child().array_index() >= self.data.len() {
+                compared = Ordering::Greater;
             }
 
             match compared {
@@ -160,6 +493,149 @@ impl<T: Ord> Beap<T> {
         }
     }
 
+    // Find the run of elements within the range in every column, starting in the bottom left corner
+    // like step_through. Both ends of the runs only ever move up as we move right through the
+    // columns, so this visits O(sqrt N) elements.
+    fn range_spans<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        steps: &mut S,
+        range: &impl RangeBounds<T>,
+    ) -> Vec<ColumnSpan> {
+        let mut spans = Vec::new();
+
+        if self.data.is_empty() {
+            return spans;
+        }
+
+        // Whether a value comes before the start of the range
+        let below = |value: &T| match range.start_bound() {
+            Bound::Included(start) => self.order(value, start) == Ordering::Less,
+            Bound::Excluded(start) => self.order(value, start) != Ordering::Greater,
+            Bound::Unbounded => false,
+        };
+
+        // Whether a value comes after the end of the range
+        let above = |value: &T| match range.end_bound() {
+            Bound::Included(end) => self.order(value, end) == Ordering::Greater,
+            Bound::Excluded(end) => self.order(value, end) != Ordering::Less,
+            Bound::Unbounded => false,
+        };
+
+        let depth = self.depth();
+        let last_row_len = BeapCoordinate::from_index(self.data.len() - 1).pos() + 1;
+
+        // The run of the current column, as offsets down the column
+        let mut start = depth;
+        let mut end = depth;
+
+        for pos in 0..depth {
+            // Columns starting past the end of the last row are one shorter
+            let column_len = if pos < last_row_len {
+                depth - pos
+            } else {
+                depth - pos - 1
+            };
+            start = start.min(column_len);
+            end = end.min(column_len);
+
+            // Move the start up while the element above it is still in the range
+            while start > 0 {
+                let coord = BeapCoordinate::new(pos + start - 1, pos);
+                steps.add_step(AnimatedSearch { coord });
+                if below(&self.data[coord.array_index()]) {
+                    break;
+                }
+                start -= 1;
+            }
+
+            // Move the end up while the element above it is still past the range
+            while end > 0 {
+                let coord = BeapCoordinate::new(pos + end - 1, pos);
+                steps.add_step(AnimatedSearch { coord });
+                if !above(&self.data[coord.array_index()]) {
+                    break;
+                }
+                end -= 1;
+            }
+
+            // Once the end reaches the top, every column to the right is past the range
+            if end == 0 {
+                break;
+            }
+
+            if start < end {
+                spans.push(ColumnSpan { pos, start, end });
+            }
+        }
+
+        spans
+    }
+
+    // Find the smallest item past a cutoff, where is_past is true for every item past the cutoff
+    fn find_smallest_item_where<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        steps: &mut S,
+        mut is_past: impl FnMut(&T) -> bool,
+    ) -> Option<BeapCoordinate> {
+        let mut found_coord = None;
+        let mut item: Option<&T> = None;
+
+        // Step based on comparison but never return equals, as we need to go through
+        // all relevant values until we reach the end
+        self.step_through(steps, |coord, value| {
+            if is_past(value) {
+                // If the value is smaller or the current item is none, set the found values
+                if item
+                    .map(|i| self.order(i, value) == Ordering::Greater)
+                    .unwrap_or(true)
+                {
+                    found_coord = Some(coord);
+                    item = Some(value);
+                }
+
+                // Move up if past the cutoff
+                Ordering::Greater
+            } else {
+                // Move down if before the cutoff
+                Ordering::Less
+            }
+        });
+
+        found_coord
+    }
+
+    // Find the largest item before a cutoff, where is_before is true for every item before the cutoff
+    fn find_largest_item_where<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        steps: &mut S,
+        mut is_before: impl FnMut(&T) -> bool,
+    ) -> Option<BeapCoordinate> {
+        let mut found_coord = None;
+        let mut item: Option<&T> = None;
+
+        // Same as find_smallest_item_where, but keeping track of the largest item on the other side
+        self.step_through(steps, |coord, value| {
+            if is_before(value) {
+                // If the value is larger or the current item is none, set the found values
+                if item
+                    .map(|i| self.order(i, value) == Ordering::Less)
+                    .unwrap_or(true)
+                {
+                    found_coord = Some(coord);
+                    item = Some(value);
+                }
+
+                // Move down if before the cutoff
+                Ordering::Less
+            } else {
+                // Move up if past the cutoff
+                Ordering::Greater
+            }
+        });
+
+        found_coord
+    }
+
     // Insert a new item into the heap
     pub fn insert(&mut self, value: T) {
         self.insert_steps::<IgnoreSteps>(value);
@@ -174,95 +650,338 @@ impl<T: Ord> Beap<T> {
         let coord = BeapCoordinate::from_index(self.data.len() - 1);
         self.bubble_up(&mut steps, coord);
 
+        self.debug_validate();
+
         steps.wrap_output(())
     }
 
-    // Pop the top item off the heap
-    pub fn pop_smallest(&mut self) -> Option<T> {
-        self.pop_smallest_steps::<IgnoreSteps>()
+    // Get the top item of the heap without removing it
+    pub fn peek_top(&self) -> Option<&T> {
+        self.data.first()
+    }
+    pub fn try_peek_top(&self) -> Result<&T, BeapError> {
+        self.peek_top().ok_or(BeapError::Empty)
     }
-    pub fn pop_smallest_steps<S: TrackSteps<AnimatedSwap>>(&mut self) -> S::WrapOutput<Option<T>> {
-        let mut steps = S::new();
 
-        // Can't pop from an empty heap
-        if self.data.is_empty() {
-            return steps.wrap_output(None);
-        }
+    // Insert all the items at once, then restore the heap property in a single pass
+    pub fn extend_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        iter: impl IntoIterator<Item = T>,
+    ) -> S::WrapOutput<()> {
+        let mut steps = S::new();
 
-        // swap_remove swaps the last element with the one at the given index and returns it
-        let first = self.data.swap_remove(0);
-        // Add the animation step for the line above
-        steps.add_step(AnimatedSwap {
-            first: BeapCoordinate::from_index(self.data.len()),
-            second: BeapCoordinate::zero(),
-            overwrite: true,
-        });
+        let old_len = self.data.len();
+        self.data.extend(iter);
+        self.restore_appended(&mut steps, old_len);
 
-        // Sink the now-first element back down thorugh the heap
-        self.sink(&mut steps, BeapCoordinate::zero());
+        self.debug_validate();
 
-        steps.wrap_output(Some(first))
+        steps.wrap_output(())
     }
 
-    // Modify a single value at a coordinate, then move it to preserve heap property
-    pub fn set_value(&mut self, coord: BeapCoordinate, value: T) -> Option<T> {
-        self.set_value_steps::<IgnoreSteps>(coord, value)
+    // Move all the items of other into this beap, leaving other empty
+    pub fn append(&mut self, other: &mut Self) {
+        self.append_steps::<IgnoreSteps>(other);
     }
-    pub fn set_value_steps<S: TrackSteps<AnimatedSwap>>(
+    pub fn append_steps<S: TrackSteps<AnimatedSwap>>(
         &mut self,
-        coord: BeapCoordinate,
-        value: T,
-    ) -> S::WrapOutput<Option<T>> {
+        other: &mut Self,
+    ) -> S::WrapOutput<()> {
         let mut steps = S::new();
 
-        if coord.array_index() >= self.data.len() {
-            return steps.wrap_output(None);
-        }
-
-        // Compare the value with the new value
-        let diff = self.data[coord.array_index()].cmp(&value);
-
-        let old = std::mem::replace(&mut self.data[coord.array_index()], value);
+        // The other beap's items always go after this one's, so that the steps replay on the array
+        // the caller sees. Its ordering can also differ even with the same type, e.g. closures
+        // capturing different state, so its layout is never reused. When it's the larger one,
+        // restore_appended rebuilds the whole beap anyway.
+        let old_len = self.data.len();
+        self.data.append(&mut other.data);
+        self.restore_appended(&mut steps, old_len);
 
-        // Based on the comparison, run the appropriate algorithm
-        match diff {
-            Ordering::Greater => self.bubble_up(&mut steps, coord),
-            Ordering::Less => self.sink(&mut steps, coord),
-            Ordering::Equal => {}
-        }
+        self.debug_validate();
 
-        steps.wrap_output(Some(old))
+        steps.wrap_output(())
     }
 
-    // Remove an item at the specified index
-    pub fn remove(&mut self, coord: BeapCoordinate) -> Option<T> {
-        self.remove_steps::<IgnoreSteps>(coord)
+    // Keep only the items that the predicate returns true for
+    pub fn retain(&mut self, keep: impl FnMut(&T) -> bool) {
+        self.retain_steps::<IgnoreSteps>(keep);
     }
-    pub fn remove_steps<S: TrackSteps<AnimatedSwap>>(
+    pub fn retain_steps<S: TrackSteps<AnimatedSwap>>(
         &mut self,
-        coord: BeapCoordinate,
-    ) -> S::WrapOutput<Option<T>> {
+        mut keep: impl FnMut(&T) -> bool,
+    ) -> S::WrapOutput<()> {
         let mut steps = S::new();
 
-        if coord.array_index() >= self.data.len() {
-            return steps.wrap_output(None);
+        self.remove_where(&mut steps, |value| !keep(value));
+
+        self.debug_validate();
+
+        steps.wrap_output(())
+    }
+
+    // Remove all the items that the predicate returns true for, returning an iterator over them
+    pub fn extract_if(&mut self, predicate: impl FnMut(&T) -> bool) -> impl Iterator<Item = T> {
+        self.extract_if_steps::<IgnoreSteps>(predicate)
+    }
+    pub fn extract_if_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        predicate: impl FnMut(&T) -> bool,
+    ) -> S::WrapOutput<impl Iterator<Item = T>> {
+        let mut steps = S::new();
+
+        let removed = self.remove_where(&mut steps, predicate);
+
+        self.debug_validate();
+
+        steps.wrap_output(removed.into_iter())
+    }
+
+    // Combine two beaps into one
+    pub fn merge(mut a: Self, mut b: Self) -> Self {
+        a.append(&mut b);
+        a
+    }
+
+    // Pop the top item off the heap
+    pub fn pop_top(&mut self) -> Option<T> {
+        self.pop_top_steps::<IgnoreSteps>()
+    }
+    pub fn pop_top_steps<S: TrackSteps<AnimatedSwap>>(&mut self) -> S::WrapOutput<Option<T>> {
+        let mut steps = S::new();
+
+        // Can't pop from an empty heap
+        if self.data.is_empty() {
+            return steps.wrap_output(None);
         }
 
-        // Swap the item with the last item
-        let last = self.data.swap_remove(coord.array_index());
-        // Add the animation step for the line above
-        steps.add_step(AnimatedSwap {
-            first: BeapCoordinate::from_index(self.data.len()),
-            second: coord,
-            overwrite: true,
+        let first = self.remove_at(&mut steps, BeapCoordinate::zero());
+
+        self.debug_validate();
+
+        steps.wrap_output(Some(first))
+    }
+
+    // Same as pop_top, but an empty beap is an error
+    pub fn try_pop_top(&mut self) -> Result<T, BeapError> {
+        self.try_pop_top_steps::<IgnoreSteps>()
+    }
+    pub fn try_pop_top_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+    ) -> S::WrapOutput<Result<T, BeapError>> {
+        let mut steps = S::new();
+
+        let first = if self.data.is_empty() {
+            Err(BeapError::Empty)
+        } else {
+            Ok(self.remove_at(&mut steps, BeapCoordinate::zero()))
+        };
+
+        self.debug_validate();
+
+        steps.wrap_output(first)
+    }
+
+    // Get the largest item of the heap without removing it
+    pub fn peek_largest(&self) -> Option<&T> {
+        self.get_coord(self.largest_coord()?)
+    }
+    pub fn try_peek_largest(&self) -> Result<&T, BeapError> {
+        self.peek_largest().ok_or(BeapError::Empty)
+    }
+
+    // Pop the largest item off the heap, making the beap usable as a double ended priority queue
+    pub fn pop_largest(&mut self) -> Option<T> {
+        self.pop_largest_steps::<IgnoreSteps>()
+    }
+    pub fn pop_largest_steps<S: TrackSteps<AnimatedSwap>>(&mut self) -> S::WrapOutput<Option<T>> {
+        let mut steps = S::new();
+
+        let largest = self
+            .largest_coord()
+            .map(|coord| self.remove_at(&mut steps, coord));
+
+        self.debug_validate();
+
+        steps.wrap_output(largest)
+    }
+
+    // Same as pop_largest, but an empty beap is an error
+    pub fn try_pop_largest(&mut self) -> Result<T, BeapError> {
+        self.try_pop_largest_steps::<IgnoreSteps>()
+    }
+    pub fn try_pop_largest_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+    ) -> S::WrapOutput<Result<T, BeapError>> {
+        let mut steps = S::new();
+
+        let largest = self
+            .largest_coord()
+            .ok_or(BeapError::Empty)
+            .map(|coord| self.remove_at(&mut steps, coord));
+
+        self.debug_validate();
+
+        steps.wrap_output(largest)
+    }
+
+    // Modify a single value at a coordinate, then move it to preserve heap property
+    pub fn set_value(&mut self, coord: BeapCoordinate, value: T) -> Option<T> {
+        self.set_value_steps::<IgnoreSteps>(coord, value)
+    }
+    pub fn set_value_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        coord: BeapCoordinate,
+        value: T,
+    ) -> S::WrapOutput<Option<T>> {
+        let mut steps = S::new();
+
+        if self.check_coord(coord).is_err() {
+            return steps.wrap_output(None);
+        }
+
+        let old = self.replace_at(&mut steps, coord, value);
+
+        self.debug_validate();
+
+        steps.wrap_output(Some(old))
+    }
+
+    // Same as set_value, but a coordinate without an item is an error
+    pub fn try_set_value(&mut self, coord: BeapCoordinate, value: T) -> Result<T, BeapError> {
+        self.try_set_value_steps::<IgnoreSteps>(coord, value)
+    }
+    pub fn try_set_value_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        coord: BeapCoordinate,
+        value: T,
+    ) -> S::WrapOutput<Result<T, BeapError>> {
+        let mut steps = S::new();
+
+        let old = self
+            .check_coord(coord)
+            .map(|()| self.replace_at(&mut steps, coord, value));
+
+        self.debug_validate();
+
+        steps.wrap_output(old)
+    }
+
+    // Get a guard for modifying the top item in place, which moves it to preserve heap property on drop
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, C>> {
+        self.get_mut(BeapCoordinate::zero())
+    }
+    pub fn try_peek_mut(&mut self) -> Result<PeekMut<'_, T, C>, BeapError> {
+        if self.data.is_empty() {
+            return Err(BeapError::Empty);
+        }
+
+        Ok(PeekMut::new(self, BeapCoordinate::zero()))
+    }
+
+    // Get a guard for modifying the item at a coordinate in place, which moves it to preserve
+    // heap property on drop
+    pub fn get_mut(&mut self, coord: BeapCoordinate) -> Option<PeekMut<'_, T, C>> {
+        self.try_get_mut(coord).ok()
+    }
+    pub fn try_get_mut(&mut self, coord: BeapCoordinate) -> Result<PeekMut<'_, T, C>, BeapError> {
+        self.check_coord(coord)?;
+
+        Ok(PeekMut::new(self, coord))
+    }
+
+    // Modify the item at a coordinate in place, then move it to preserve heap property.
+    // Returns the coordinate the item ended up at.
+    pub fn modify(
+        &mut self,
+        coord: BeapCoordinate,
+        f: impl FnOnce(&mut T),
+    ) -> Option<BeapCoordinate> {
+        self.modify_steps::<IgnoreSteps>(coord, f)
+    }
+    pub fn modify_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        coord: BeapCoordinate,
+        f: impl FnOnce(&mut T),
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        let mut steps = S::new();
+
+        if self.check_coord(coord).is_err() {
+            return steps.wrap_output(None);
+        }
+
+        f(&mut self.data[coord.array_index()]);
+        let moved = self.restore(&mut steps, coord);
+
+        self.debug_validate();
+
+        steps.wrap_output(Some(moved))
+    }
+
+    // Same as modify, but a coordinate without an item is an error
+    pub fn try_modify(
+        &mut self,
+        coord: BeapCoordinate,
+        f: impl FnOnce(&mut T),
+    ) -> Result<BeapCoordinate, BeapError> {
+        self.try_modify_steps::<IgnoreSteps>(coord, f)
+    }
+    pub fn try_modify_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        coord: BeapCoordinate,
+        f: impl FnOnce(&mut T),
+    ) -> S::WrapOutput<Result<BeapCoordinate, BeapError>> {
+        let mut steps = S::new();
+
+        let moved = self.check_coord(coord).map(|()| {
+            f(&mut self.data[coord.array_index()]);
+            self.restore(&mut steps, coord)
         });
 
-        // Sink the element back down thorugh the heap
-        self.sink(&mut steps, coord);
+        self.debug_validate();
+
+        steps.wrap_output(moved)
+    }
+
+    // Remove an item at the specified index
+    pub fn remove(&mut self, coord: BeapCoordinate) -> Option<T> {
+        self.remove_steps::<IgnoreSteps>(coord)
+    }
+    pub fn remove_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        coord: BeapCoordinate,
+    ) -> S::WrapOutput<Option<T>> {
+        let mut steps = S::new();
+
+        if self.check_coord(coord).is_err() {
+            return steps.wrap_output(None);
+        }
+
+        let last = self.remove_at(&mut steps, coord);
+
+        self.debug_validate();
 
         steps.wrap_output(Some(last))
     }
 
+    // Same as remove, but a coordinate without an item is an error
+    pub fn try_remove(&mut self, coord: BeapCoordinate) -> Result<T, BeapError> {
+        self.try_remove_steps::<IgnoreSteps>(coord)
+    }
+    pub fn try_remove_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        coord: BeapCoordinate,
+    ) -> S::WrapOutput<Result<T, BeapError>> {
+        let mut steps = S::new();
+
+        let removed = self
+            .check_coord(coord)
+            .map(|()| self.remove_at(&mut steps, coord));
+
+        self.debug_validate();
+
+        steps.wrap_output(removed)
+    }
+
     // Function for finding an item coordinate by value
     pub fn find_item(&self, value: &T) -> Option<BeapCoordinate> {
         self.find_item_steps::<IgnoreSteps>(value)
@@ -270,15 +989,87 @@ impl<T: Ord> Beap<T> {
     pub fn find_item_steps<S: TrackSteps<AnimatedSearch>>(
         &self,
         item: &T,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        // Step based on direct comparison
+        self.search_by_steps::<S>(|value| self.order(value, item))
+    }
+
+    // Find an item using a function that tells whether an item is before (Less), after (Greater)
+    // or at (Equal) the target, same as slice::binary_search_by. Returns any one matching item.
+    // The search walks the beap like a sorted matrix, so the function has to be monotone with the
+    // beap's ordering: for items a <= b in the beap, f(a) must be <= f(b). Otherwise the search
+    // can miss matching items, though it still ends after O(sqrt N) steps.
+    pub fn search_by(&self, f: impl FnMut(&T) -> Ordering) -> Option<BeapCoordinate> {
+        self.search_by_steps::<IgnoreSteps>(f)
+    }
+    pub fn search_by_steps<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        mut f: impl FnMut(&T) -> Ordering,
     ) -> S::WrapOutput<Option<BeapCoordinate>> {
         let mut steps = S::new();
 
-        // Step based on direct comparison
-        let coord = self.step_through(&mut steps, |_, value| value.cmp(item));
+        let coord = self.step_through(&mut steps, |_, value| f(value));
 
         steps.wrap_output(coord)
     }
 
+    // Find an item whose key is equal to the given one, e.g. a task by its deadline.
+    // Same as search_by, so the keys have to go up along with the beap's ordering, as they do
+    // when the beap is ordered by the key itself.
+    pub fn search_by_key<K: Ord>(&self, key: &K, f: impl FnMut(&T) -> K) -> Option<BeapCoordinate> {
+        self.search_by_key_steps::<IgnoreSteps, K>(key, f)
+    }
+    pub fn search_by_key_steps<S: TrackSteps<AnimatedSearch>, K: Ord>(
+        &self,
+        key: &K,
+        mut f: impl FnMut(&T) -> K,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        self.search_by_steps::<S>(|value| f(value).cmp(key))
+    }
+
+    // Function for finding the coordinates of every item equal to a value
+    pub fn find_all<'a>(&'a self, value: &T) -> impl ExactSizeIterator<Item = BeapCoordinate> + 'a {
+        self.find_all_steps::<IgnoreSteps>(value)
+    }
+    pub fn find_all_steps<'a, S: TrackSteps<AnimatedSearch>>(
+        &'a self,
+        item: &T,
+    ) -> S::WrapOutput<impl ExactSizeIterator<Item = BeapCoordinate> + 'a> {
+        let mut steps = S::new();
+
+        // Equal items form a run in each column, so keep walking the staircase after the first one
+        let spans = self.range_spans(&mut steps, &(Bound::Included(item), Bound::Included(item)));
+        let found = Range::new(&self.data, spans).map(|(coord, _)| coord);
+
+        steps.wrap_output(found)
+    }
+
+    // Count the items equal to a value, without visiting each of them
+    pub fn count(&self, value: &T) -> usize {
+        self.find_all(value).len()
+    }
+
+    // Remove every item equal to a value, returning the removed items
+    pub fn remove_all(&mut self, value: &T) -> Vec<T> {
+        self.remove_all_steps::<IgnoreSteps>(value)
+    }
+    pub fn remove_all_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        item: &T,
+    ) -> S::WrapOutput<Vec<T>> {
+        let mut steps = S::new();
+        let mut removed = Vec::new();
+
+        // Removing moves other items around, so search again after every removal
+        while let Some(coord) = self.find_item(item) {
+            removed.push(self.remove_at(&mut steps, coord));
+        }
+
+        self.debug_validate();
+
+        steps.wrap_output(removed)
+    }
+
     // Function for finding an item coordinate by value
     pub fn find_smallest_item_greater_than(&self, value: &T) -> Option<BeapCoordinate> {
         self.find_smallest_item_greater_than_steps::<IgnoreSteps>(value)
@@ -288,33 +1079,198 @@ impl<T: Ord> Beap<T> {
         greater_than: &T,
     ) -> S::WrapOutput<Option<BeapCoordinate>> {
         let mut steps = S::new();
-        let mut found_coord = None;
-        let mut item: Option<&T> = None;
 
-        // Step based on comparison but never return equals, as we need to go through
-        // all relevant values until we reach the end
-        self.step_through(&mut steps, |coord, value| {
-            if value > greater_than {
-                // If the value is smaller or the current item is none, set the found values
-                if item.map(|i| i > value).unwrap_or(true) {
-                    found_coord = Some(coord);
-                    item = Some(value);
-                }
+        let found_coord = self.find_smallest_item_where(&mut steps, |value| {
+            self.order(value, greater_than) == Ordering::Greater
+        });
 
-                // Move up if below cutoff
-                Ordering::Greater
-            } else {
-                // Move down if above cutoff
-                Ordering::Less
-            }
+        steps.wrap_output(found_coord)
+    }
+
+    pub fn find_smallest_item_greater_than_or_equal(&self, value: &T) -> Option<BeapCoordinate> {
+        self.find_smallest_item_greater_than_or_equal_steps::<IgnoreSteps>(value)
+    }
+    pub fn find_smallest_item_greater_than_or_equal_steps<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        greater_than: &T,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        let mut steps = S::new();
+
+        let found_coord = self.find_smallest_item_where(&mut steps, |value| {
+            self.order(value, greater_than) != Ordering::Less
         });
 
         steps.wrap_output(found_coord)
     }
 
+    pub fn find_largest_item_less_than(&self, value: &T) -> Option<BeapCoordinate> {
+        self.find_largest_item_less_than_steps::<IgnoreSteps>(value)
+    }
+    pub fn find_largest_item_less_than_steps<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        less_than: &T,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        let mut steps = S::new();
+
+        let found_coord = self.find_largest_item_where(&mut steps, |value| {
+            self.order(value, less_than) == Ordering::Less
+        });
+
+        steps.wrap_output(found_coord)
+    }
+
+    pub fn find_largest_item_less_than_or_equal(&self, value: &T) -> Option<BeapCoordinate> {
+        self.find_largest_item_less_than_or_equal_steps::<IgnoreSteps>(value)
+    }
+    pub fn find_largest_item_less_than_or_equal_steps<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        less_than: &T,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        let mut steps = S::new();
+
+        let found_coord = self.find_largest_item_where(&mut steps, |value| {
+            self.order(value, less_than) != Ordering::Greater
+        });
+
+        steps.wrap_output(found_coord)
+    }
+
+    // Iterate over every element within the range, in O(sqrt N) plus the number of elements found
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, T> {
+        self.range_steps::<IgnoreSteps, R>(range)
+    }
+    pub fn range_steps<S: TrackSteps<AnimatedSearch>, R: RangeBounds<T>>(
+        &self,
+        range: R,
+    ) -> S::WrapOutput<Range<'_, T>> {
+        let mut steps = S::new();
+
+        let spans = self.range_spans(&mut steps, &range);
+
+        steps.wrap_output(Range::new(&self.data, spans))
+    }
+
+    // Count the items within the range. Only the ends of the run in each column are visited, so
+    // this is O(sqrt N) however many items are counted.
+    pub fn count_in_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        self.count_in_range_steps::<IgnoreSteps, R>(range)
+    }
+    pub fn count_in_range_steps<S: TrackSteps<AnimatedSearch>, R: RangeBounds<T>>(
+        &self,
+        range: R,
+    ) -> S::WrapOutput<usize> {
+        let mut steps = S::new();
+
+        let spans = self.range_spans(&mut steps, &range);
+        let count = spans.iter().map(ColumnSpan::len).sum();
+
+        steps.wrap_output(count)
+    }
+
+    // Count the items smaller than a value, i.e. the rank the value would have in the beap
+    pub fn count_less_than(&self, value: &T) -> usize {
+        self.count_less_than_steps::<IgnoreSteps>(value)
+    }
+    pub fn count_less_than_steps<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        value: &T,
+    ) -> S::WrapOutput<usize> {
+        self.count_in_range_steps::<S, _>((Bound::Unbounded, Bound::Excluded(value)))
+    }
+
+    // Find the k-th smallest item, counting from 0, without modifying the beap.
+    // Every item is smaller than or equal to its children, so the items can be visited in order by
+    // always popping the smallest item of a frontier and adding its children. Each coordinate gets
+    // added by only one of its parents, the left child by the item above it in the same column and
+    // the right child only from the end of a row. This takes O(k sqrt k) time and O(k) space.
+    pub fn nth_smallest(&self, k: usize) -> Option<BeapCoordinate> {
+        self.nth_smallest_steps::<IgnoreSteps>(k)
+    }
+    pub fn nth_smallest_steps<S: TrackSteps<AnimatedSearch>>(
+        &self,
+        k: usize,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        let mut steps = S::new();
+
+        if k >= self.data.len() {
+            return steps.wrap_output(None);
+        }
+
+        // The frontier is a beap of coordinates, ordered by the items at them
+        let mut frontier = Beap::new_by(|a: &BeapCoordinate, b: &BeapCoordinate| {
+            self.order(&self.data[a.array_index()], &self.data[b.array_index()])
+        });
+        frontier.insert(BeapCoordinate::zero());
+
+        let mut nth = None;
+        let mut popped = 0;
+        while let Some(coord) = frontier.pop_top() {
+            steps.add_step(AnimatedSearch { coord });
+
+            if popped == k {
+                nth = Some(coord);
+                break;
+            }
+            popped += 1;
+
+            let left_child = coord.left_child();
+            if left_child.array_index() < self.data.len() {
+                frontier.insert(left_child);
+            }
+            let right_child = coord.right_child();
+            if coord.pos() == coord.row() && right_child.array_index() < self.data.len() {
+                frontier.insert(right_child);
+            }
+        }
+
+        steps.wrap_output(nth)
+    }
+
+    // Consume the beap, returning its items sorted from smallest to largest
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let Self { mut data, compare } = self;
+        data.sort_by(|a, b| compare.compare(a, b));
+        data
+    }
+
+    // Consume the beap, returning an iterator that pops its items from smallest to largest
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, C> {
+        IntoIterSorted::new(self)
+    }
+
+    // Return an iterator that pops the items from smallest to largest, leaving the beap empty
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, C> {
+        DrainSorted::new(self)
+    }
+
+    // Check that every item is smaller than or equal to its children, reporting the first one that isn't.
+    // This is O(N), so it's meant for checking beaps that came from somewhere else, e.g. from disk.
+    pub fn validate(&self) -> Result<(), HeapViolation> {
+        for (coord, parent) in self.iter_coords() {
+            let children = [
+                (coord.left_child(), ChildSide::Left),
+                (coord.right_child(), ChildSide::Right),
+            ];
+
+            for (child, side) in children {
+                if let Some(child_value) = self.data.get(child.array_index()) {
+                    if self.order(parent, child_value) == Ordering::Greater {
+                        return Err(HeapViolation {
+                            parent: coord,
+                            child,
+                            side,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn depth(&self) -> usize {
-        if self.data.len() == 0 {
-            return 0;
+        if self.data.is_empty() {
+            0
         } else {
             BeapCoordinate::from_index(self.data.len() - 1).row() + 1
         }
@@ -324,15 +1280,162 @@ impl<T: Ord> Beap<T> {
         self.data.len()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    // Reserve space for at least additional more items
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
+    // Remove all the items, keeping the allocated space
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    // Iterate over the items in the order they are laid out in the array, row by row
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.data.iter()
     }
 
+    // Iterate over the items along with their coordinates, in the same order as iter
+    pub fn iter_coords(&self) -> impl Iterator<Item = (BeapCoordinate, &T)> {
+        let coords = std::iter::successors(Some(BeapCoordinate::zero()), |coord| {
+            Some(coord.next_in_order())
+        });
+        coords.zip(&self.data)
+    }
+
+    // Iterate over the rows from the top down, where only the last one can be shorter than its row_len
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.depth()).map(move |row| self.row_slice(row))
+    }
+
+    // The items in a row, or None if the beap doesn't reach that row
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row < self.depth() {
+            Some(self.row_slice(row))
+        } else {
+            None
+        }
+    }
+
+    // The items in a row that's within the depth
+    fn row_slice(&self, row: usize) -> &[T] {
+        let start = BeapCoordinate::row_start_index(row);
+        let end = (start + BeapCoordinate::row_len(row)).min(self.data.len());
+        &self.data[start..end]
+    }
+
+    // The items in the order they are laid out in the array, row by row, as used by array_index
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    // Consume the beap, returning the items in the order they are laid out in the array
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
     pub fn get_index(&self, index: usize) -> Option<&T> {
         self.data.get(index)
     }
 
     pub fn get_coord(&self, coord: BeapCoordinate) -> Option<&T> {
-        self.data.get(coord.array_index())
+        self.try_get_coord(coord).ok()
+    }
+    pub fn try_get_coord(&self, coord: BeapCoordinate) -> Result<&T, BeapError> {
+        self.check_coord(coord)?;
+        Ok(&self.data[coord.array_index()])
+    }
+}
+
+// Same as from_vec_by, so O(N log N)
+impl<T, C: Compare<T> + Default> From<Vec<T>> for Beap<T, C> {
+    fn from(data: Vec<T>) -> Self {
+        Self::from_vec_by(data, C::default())
+    }
+}
+
+// Collects into a vector and then uses from_vec_by, so O(N log N)
+impl<T, C: Compare<T> + Default> FromIterator<T> for Beap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec_by(iter.into_iter().collect(), C::default())
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for Beap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_steps::<IgnoreSteps>(iter);
+    }
+}
+
+impl<'a, T: Copy + 'a, C: Compare<T>> Extend<&'a T> for Beap<T, C> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+// Iterates over the items in the order they are laid out in the array, same as iter
+impl<T, C> IntoIterator for Beap<T, C> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
     }
 }
+
+impl<'a, T, C> IntoIterator for &'a Beap<T, C> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+// Panics if the coordinate is out of bounds, use get_coord to avoid that
+impl<T, C> Index<BeapCoordinate> for Beap<T, C> {
+    type Output = T;
+
+    fn index(&self, coord: BeapCoordinate) -> &T {
+        if let Err(error) = self.check_coord(coord) {
+            panic!("{}", error);
+        }
+        &self.data[coord.array_index()]
+    }
+}
+
+// The items in the order they are laid out in the array
+impl<T, C> From<Beap<T, C>> for Vec<T> {
+    fn from(beap: Beap<T, C>) -> Self {
+        beap.data
+    }
+}
+
+// Two beaps are equal if they contain the same items, however they're laid out
+impl<T: Ord, C> PartialEq for Beap<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.data.len() != other.data.len() {
+            return false;
+        }
+
+        let mut a: Vec<&T> = self.data.iter().collect();
+        let mut b: Vec<&T> = other.data.iter().collect();
+        a.sort_unstable();
+        b.sort_unstable();
+        a == b
+    }
+}
+
+impl<T: Ord, C> Eq for Beap<T, C> {}
@@ -22,6 +22,12 @@ impl<T> StepTracker<T> {
     }
 }
 
+impl<T> Default for StepTracker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> TrackSteps<T> for StepTracker<T> {
     type WrapOutput<O> = ResultWithSteps<O, Vec<T>>;
 
@@ -0,0 +1,35 @@
+use std::cmp::Ordering;
+
+// The ordering a beap keeps its elements in.
+// The element that compares as the smallest always sits at the top of the beap, so a max beap
+// is just a beap with a reversed ordering. Every search and modification goes through this.
+pub trait Compare<T: ?Sized> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+// Orders by the element's Ord implementation, keeping the smallest element at the top
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MinOrder;
+
+impl<T: Ord + ?Sized> Compare<T> for MinOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+// Orders by the reverse of the element's Ord implementation, keeping the largest element at the top
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MaxOrder;
+
+impl<T: Ord + ?Sized> Compare<T> for MaxOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+// Any comparison closure can be used as an ordering directly
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
@@ -1,3 +1,5 @@
+use super::CoordinateError;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct BeapCoordinate {
     row: usize,
@@ -5,6 +7,11 @@ pub struct BeapCoordinate {
 }
 
 impl BeapCoordinate {
+    // The last row whose every coordinate has an array index that fits in a usize.
+    // Coordinates in rows past this one can't be stored in a beap, so the checked functions and
+    // conversions reject them.
+    pub const MAX_ROW: usize = max_row();
+
     pub(super) fn new(row: usize, pos: usize) -> Self {
         Self { row, pos }
     }
@@ -19,20 +26,42 @@ impl BeapCoordinate {
     }
 
     pub fn from_index(index: usize) -> Self {
-        // subtract row sizes until fits
-        let mut row = 1;
-        let mut pos = index;
-        while pos >= row {
-            pos -= row;
-            row += 1;
-        }
-        Self { row: row - 1, pos }
+        // The row is the largest one starting at or before the index, i.e. the largest row where
+        // row * (row + 1) / 2 <= index. Solving that gives row = (sqrt(8 * index + 1) - 1) / 2,
+        // which is done in u128 so that it can't overflow for any index.
+        let row = ((isqrt(8 * index as u128 + 1) - 1) / 2) as usize;
+        let pos = index - Self::row_start_index(row);
+        Self { row, pos }
     }
 
     pub fn zero() -> Self {
         Self::new(0, 0)
     }
 
+    // The array index of the first coordinate in a row, i.e. row * (row + 1) / 2.
+    // Whichever of row and row + 1 is even gets halved first, so it only overflows if the result does.
+    pub fn row_start_index(row: usize) -> usize {
+        if row % 2 == 0 {
+            row / 2 * (row + 1)
+        } else {
+            (row + 1) / 2 * row
+        }
+    }
+
+    // Same as row_start_index but returns None instead of overflowing
+    pub fn checked_row_start_index(row: usize) -> Option<usize> {
+        if row % 2 == 0 {
+            (row / 2).checked_mul(row.checked_add(1)?)
+        } else {
+            (row / 2 + 1).checked_mul(row)
+        }
+    }
+
+    // The number of coordinates in a row, which grows by one with every row
+    pub fn row_len(row: usize) -> usize {
+        row + 1
+    }
+
     pub fn row(&self) -> usize {
         self.row
     }
@@ -42,7 +71,12 @@ impl BeapCoordinate {
     }
 
     pub fn array_index(&self) -> usize {
-        self.row * (self.row + 1) / 2 + self.pos
+        Self::row_start_index(self.row) + self.pos
+    }
+
+    // Same as array_index but returns None instead of overflowing
+    pub fn checked_array_index(&self) -> Option<usize> {
+        Self::checked_row_start_index(self.row)?.checked_add(self.pos)
     }
 
     pub fn left_parent(&self) -> Option<Self> {
@@ -74,4 +108,116 @@ impl BeapCoordinate {
         let pos = self.pos;
         Self::new(self.row + 1, pos + 1)
     }
+
+    // The coordinate after this one in array order, i.e. the one with array index + 1
+    pub fn next_in_order(&self) -> Self {
+        if self.pos < self.row {
+            Self::new(self.row, self.pos + 1)
+        } else {
+            Self::new(self.row + 1, 0)
+        }
+    }
+
+    // The coordinates next to this one in its row, which are the coordinates sharing a parent with it
+    pub fn siblings(&self) -> impl Iterator<Item = Self> {
+        let left = self.left_parent().map(|parent| parent.left_child());
+        let right = self.right_parent().map(|parent| parent.right_child());
+        left.into_iter().chain(right)
+    }
+
+    // Every coordinate that this one can be reached from by stepping down through children,
+    // row by row going up from the parents to the root.
+    // These are the coordinates whose values are known to be smaller than or equal to this one's.
+    pub fn ancestors(&self) -> impl Iterator<Item = Self> {
+        let Self { row, pos } = *self;
+        (1..=row).flat_map(move |up| {
+            let ancestor_row = row - up;
+            (pos.saturating_sub(up)..=pos.min(ancestor_row))
+                .map(move |ancestor_pos| Self::new(ancestor_row, ancestor_pos))
+        })
+    }
+
+    // Every coordinate that can be reached from this one by stepping down through children,
+    // row by row going down from the children.
+    // The rows never run out, so this has to be cut off, e.g. with take_while on the array index.
+    pub fn descendants(&self) -> impl Iterator<Item = Self> {
+        let Self { row, pos } = *self;
+        (1..).flat_map(move |down| {
+            (pos..=pos + down).map(move |descendant_pos| Self::new(row + down, descendant_pos))
+        })
+    }
+
+    // Same as left_child but returns None if the child would be past MAX_ROW
+    pub fn checked_left_child(&self) -> Option<Self> {
+        if self.row >= Self::MAX_ROW {
+            return None;
+        }
+        Some(self.left_child())
+    }
+
+    // Same as right_child but returns None if the child would be past MAX_ROW
+    pub fn checked_right_child(&self) -> Option<Self> {
+        if self.row >= Self::MAX_ROW {
+            return None;
+        }
+        Some(self.right_child())
+    }
+}
+
+// Convert an array index to its coordinate.
+// Unlike from_index this fails for the indexes in the partial row past MAX_ROW.
+impl TryFrom<usize> for BeapCoordinate {
+    type Error = CoordinateError;
+
+    fn try_from(index: usize) -> Result<Self, Self::Error> {
+        let coord = Self::from_index(index);
+        if coord.row > Self::MAX_ROW {
+            return Err(CoordinateError::RowTooLarge { row: coord.row });
+        }
+        Ok(coord)
+    }
+}
+
+// Convert a (row, pos) pair to a coordinate, checking that it's valid
+impl TryFrom<(usize, usize)> for BeapCoordinate {
+    type Error = CoordinateError;
+
+    fn try_from((row, pos): (usize, usize)) -> Result<Self, Self::Error> {
+        if row > Self::MAX_ROW {
+            return Err(CoordinateError::RowTooLarge { row });
+        }
+        if pos > row {
+            return Err(CoordinateError::PosOutOfRow { row, pos });
+        }
+        Ok(Self { row, pos })
+    }
+}
+
+// Binary search for the largest row where the last index, row * (row + 1) / 2 + row, fits in a usize
+const fn max_row() -> usize {
+    let max = usize::MAX as u128;
+    let mut low: u128 = 0;
+    // The answer is around sqrt(2 * max), so this bound keeps mid * (mid + 3) within a u128
+    let mut high: u128 = max / 2;
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if mid * (mid + 3) / 2 <= max {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low as usize
+}
+
+// Exact integer square root, starting from the floating point one and correcting its rounding
+fn isqrt(n: u128) -> u128 {
+    let mut root = (n as f64).sqrt() as u128;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
 }
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use super::BeapCoordinate;
+
+// Which of a parent's two children an error refers to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChildSide {
+    Left,
+    Right,
+}
+
+// A place where the heap property doesn't hold, i.e. a child that is smaller than its parent
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HeapViolation {
+    pub parent: BeapCoordinate,
+    pub child: BeapCoordinate,
+    pub side: ChildSide,
+}
+
+impl fmt::Display for HeapViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = match self.side {
+            ChildSide::Left => "left",
+            ChildSide::Right => "right",
+        };
+
+        write!(
+            f,
+            "heap property violated: the {} child at ({}, {}) is smaller than its parent at ({}, {})",
+            side,
+            self.child.row(),
+            self.child.pos(),
+            self.parent.row(),
+            self.parent.pos(),
+        )
+    }
+}
+
+impl std::error::Error for HeapViolation {}
+
+// Why a row and pos or an array index couldn't be turned into a BeapCoordinate
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CoordinateError {
+    // The pos is past the end of the row, i.e. greater than the row
+    PosOutOfRow { row: usize, pos: usize },
+    // The row is past BeapCoordinate::MAX_ROW, so its array indexes don't fit in a usize
+    RowTooLarge { row: usize },
+}
+
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PosOutOfRow { row, pos } => {
+                write!(f, "pos {} is past the end of row {}", pos, row)
+            }
+            Self::RowTooLarge { row } => write!(
+                f,
+                "row {} is past the largest indexable row {}",
+                row,
+                BeapCoordinate::MAX_ROW
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CoordinateError {}
+
+// Why an operation on a beap failed, returned by the try_ functions
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BeapError {
+    // The operation needs at least one item
+    Empty,
+    // The coordinate is valid, but the beap doesn't have an item there
+    CoordinateOutOfBounds { coord: BeapCoordinate, len: usize },
+    // The coordinate can't exist in any beap
+    InvalidCoordinate(CoordinateError),
+}
+
+impl From<CoordinateError> for BeapError {
+    fn from(error: CoordinateError) -> Self {
+        Self::InvalidCoordinate(error)
+    }
+}
+
+impl fmt::Display for BeapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the beap is empty"),
+            Self::CoordinateOutOfBounds { coord, len } => write!(
+                f,
+                "coordinate ({}, {}) is out of bounds for a beap of {} items",
+                coord.row(),
+                coord.pos(),
+                len
+            ),
+            Self::InvalidCoordinate(error) => write!(f, "invalid coordinate: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for BeapError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidCoordinate(error) => Some(error),
+            _ => None,
+        }
+    }
+}
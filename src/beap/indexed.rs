@@ -0,0 +1,308 @@
+use super::{
+    animation_util::{AnimatedSwap, IgnoreSteps, TrackSteps},
+    Beap, BeapCoordinate, Compare, MinOrder,
+};
+
+// A stable reference to an element of an IndexedBeap.
+// Unlike a coordinate, a handle keeps pointing to the same element however much it moves around.
+// The slots of removed elements get reused, but with a new generation, so a handle to a removed
+// element stays invalid.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle {
+    slot: usize,
+    generation: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Slot {
+    // Bumped every time the slot's element is removed, invalidating the handles given out for it
+    generation: usize,
+    // The array index of the slot's element, or None if the slot is free
+    index: Option<usize>,
+}
+
+// The two way mapping between handles and array indexes
+#[derive(Debug, Clone, Default)]
+struct HandleTable {
+    slots: Vec<Slot>,
+    // The slots that can be reused, which keeps the table as large as the most elements at once
+    free: Vec<usize>,
+    // The handle of the element at each array index
+    handles: Vec<Handle>,
+}
+
+impl HandleTable {
+    // Get a handle for a new element at the end of the array
+    fn allocate(&mut self) -> Handle {
+        let index = Some(self.handles.len());
+
+        let handle = if let Some(slot) = self.free.pop() {
+            self.slots[slot].index = index;
+            Handle {
+                slot,
+                generation: self.slots[slot].generation,
+            }
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                index,
+            });
+            Handle {
+                slot: self.slots.len() - 1,
+                generation: 0,
+            }
+        };
+
+        self.handles.push(handle);
+        handle
+    }
+
+    // The array index of the handle's element, if it's still in the heap
+    fn index(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.slot)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.index
+    }
+
+    fn set_index(&mut self, handle: Handle, index: usize) {
+        self.slots[handle.slot].index = Some(index);
+    }
+
+    fn apply(&mut self, step: &AnimatedSwap) {
+        let first = step.first.array_index();
+        let second = step.second.array_index();
+
+        if step.overwrite {
+            // The last element overwrites the second one, same as the beap's swap_remove
+            debug_assert_eq!(first, self.handles.len() - 1);
+            let removed = self.handles.swap_remove(second);
+            let slot = &mut self.slots[removed.slot];
+            slot.index = None;
+            slot.generation += 1;
+            self.free.push(removed.slot);
+
+            if let Some(&moved) = self.handles.get(second) {
+                self.set_index(moved, second);
+            }
+        } else {
+            self.handles.swap(first, second);
+            self.set_index(self.handles[first], first);
+            self.set_index(self.handles[second], second);
+        }
+    }
+}
+
+// Step tracker that keeps the handle table up to date with every swap the beap makes,
+// while still passing the steps on to the caller's tracker
+struct TrackHandles<S> {
+    table: HandleTable,
+    steps: S,
+}
+
+impl<S: TrackSteps<AnimatedSwap>> TrackSteps<AnimatedSwap> for TrackHandles<S> {
+    type WrapOutput<O> = S::WrapOutput<O>;
+
+    fn new() -> Self {
+        Self {
+            table: HandleTable::default(),
+            steps: S::new(),
+        }
+    }
+
+    fn add_step(&mut self, step: AnimatedSwap) {
+        self.table.apply(&step);
+        self.steps.add_step(step);
+    }
+
+    fn wrap_output<O>(self, output: O) -> Self::WrapOutput<O> {
+        self.steps.wrap_output(output)
+    }
+}
+
+// Moves the handle table into a tracker for the length of an operation, and back when dropped.
+// Putting it back on drop keeps the IndexedBeap usable even if a comparison panics partway through.
+struct TableGuard<'a, S> {
+    home: &'a mut HandleTable,
+    tracker: TrackHandles<S>,
+}
+
+impl<'a, S: TrackSteps<AnimatedSwap>> TableGuard<'a, S> {
+    fn new(home: &'a mut HandleTable) -> Self {
+        let tracker = TrackHandles {
+            table: std::mem::take(home),
+            steps: S::new(),
+        };
+        Self { home, tracker }
+    }
+
+    // Put the table back, returning the caller's steps
+    fn into_steps(mut self) -> S {
+        std::mem::replace(&mut self.tracker.steps, S::new())
+    }
+}
+
+impl<'a, S> Drop for TableGuard<'a, S> {
+    fn drop(&mut self) {
+        std::mem::swap(self.home, &mut self.tracker.table);
+    }
+}
+
+// A beap that hands out a Handle for every inserted element, which can later be used to get,
+// update or remove that element in O(sqrt N) without searching for it.
+// This makes it usable for decrease-key workloads such as Dijkstra's algorithm.
+#[derive(Debug, Clone)]
+pub struct IndexedBeap<T, C = MinOrder> {
+    beap: Beap<T, C>,
+    table: HandleTable,
+}
+
+impl<T, C: Default> Default for IndexedBeap<T, C> {
+    fn default() -> Self {
+        Self {
+            beap: Beap::default(),
+            table: HandleTable::default(),
+        }
+    }
+}
+
+impl<T: Ord> IndexedBeap<T> {
+    pub fn new() -> Self {
+        Self::new_by(MinOrder)
+    }
+}
+
+impl<T, C: Compare<T>> IndexedBeap<T, C> {
+    pub fn new_by(compare: C) -> Self {
+        Self {
+            beap: Beap::new_by(compare),
+            table: HandleTable::default(),
+        }
+    }
+
+    fn finish<S: TrackSteps<AnimatedSwap>, O>(&self, steps: S, output: O) -> S::WrapOutput<O> {
+        self.beap.debug_validate();
+        steps.wrap_output(output)
+    }
+
+    // Insert a new item into the heap, returning a handle to it
+    pub fn insert(&mut self, value: T) -> Handle {
+        self.insert_steps::<IgnoreSteps>(value)
+    }
+    pub fn insert_steps<S: TrackSteps<AnimatedSwap>>(&mut self, value: T) -> S::WrapOutput<Handle> {
+        let mut guard = TableGuard::<S>::new(&mut self.table);
+
+        // Register the handle at the end, where the item is added
+        let handle = guard.tracker.table.allocate();
+        self.beap.data.push(value);
+
+        // Bubble it upwards through the heap
+        let coord = BeapCoordinate::from_index(self.beap.data.len() - 1);
+        self.beap.bubble_up(&mut guard.tracker, coord);
+
+        let steps = guard.into_steps();
+        self.finish(steps, handle)
+    }
+
+    // Pop the top item off the heap, along with the handle it had
+    pub fn pop_top(&mut self) -> Option<(Handle, T)> {
+        self.pop_top_steps::<IgnoreSteps>()
+    }
+    pub fn pop_top_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+    ) -> S::WrapOutput<Option<(Handle, T)>> {
+        let mut guard = TableGuard::<S>::new(&mut self.table);
+
+        let popped = guard.tracker.table.handles.first().copied().map(|handle| {
+            let value = self
+                .beap
+                .remove_at(&mut guard.tracker, BeapCoordinate::zero());
+            (handle, value)
+        });
+
+        let steps = guard.into_steps();
+        self.finish(steps, popped)
+    }
+
+    // Modify the value of the handle's element, then move it to preserve heap property
+    pub fn update(&mut self, handle: Handle, value: T) -> Option<T> {
+        self.update_steps::<IgnoreSteps>(handle, value)
+    }
+    pub fn update_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        handle: Handle,
+        value: T,
+    ) -> S::WrapOutput<Option<T>> {
+        let coord = self.coord(handle);
+        let mut guard = TableGuard::<S>::new(&mut self.table);
+
+        let old = coord.map(|coord| self.beap.replace_at(&mut guard.tracker, coord, value));
+
+        let steps = guard.into_steps();
+        self.finish(steps, old)
+    }
+
+    // Remove the handle's element from the heap
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        self.remove_steps::<IgnoreSteps>(handle)
+    }
+    pub fn remove_steps<S: TrackSteps<AnimatedSwap>>(
+        &mut self,
+        handle: Handle,
+    ) -> S::WrapOutput<Option<T>> {
+        let coord = self.coord(handle);
+        let mut guard = TableGuard::<S>::new(&mut self.table);
+
+        let removed = coord.map(|coord| self.beap.remove_at(&mut guard.tracker, coord));
+
+        let steps = guard.into_steps();
+        self.finish(steps, removed)
+    }
+
+    pub fn peek_top(&self) -> Option<(Handle, &T)> {
+        let handle = *self.table.handles.first()?;
+        Some((handle, self.beap.peek_top()?))
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.beap.get_coord(self.coord(handle)?)
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.coord(handle).is_some()
+    }
+
+    // Get the current coordinate of the handle's element, if it's still in the heap
+    pub fn coord(&self, handle: Handle) -> Option<BeapCoordinate> {
+        let index = self.table.index(handle)?;
+        Some(BeapCoordinate::from_index(index))
+    }
+
+    // Get the handle of the element at a coordinate, e.g. one returned by a search on as_beap
+    pub fn handle_at(&self, coord: BeapCoordinate) -> Option<Handle> {
+        self.table
+            .handles
+            .get(coord.checked_array_index()?)
+            .copied()
+    }
+
+    // The underlying beap, for searching and reading the elements by coordinate
+    pub fn as_beap(&self) -> &Beap<T, C> {
+        &self.beap
+    }
+
+    pub fn len(&self) -> usize {
+        self.beap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.beap.is_empty()
+    }
+
+    // The number of handle slots, which only grows with the most elements held at once
+    #[cfg(test)]
+    pub(crate) fn slot_count(&self) -> usize {
+        self.table.slots.len()
+    }
+}
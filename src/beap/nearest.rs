@@ -0,0 +1,85 @@
+use std::cmp::Ordering;
+
+use super::{
+    animation_util::{AnimatedSearch, IgnoreSteps, TrackSteps},
+    Beap, BeapCoordinate, Compare,
+};
+
+// Which item find_nearest picks when the closest items on either side of the target are equally far
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    // The item ordered before the target, i.e. the one nearer the top of the beap
+    #[default]
+    PreferLower,
+    // The item ordered after the target
+    PreferHigher,
+}
+
+impl<T, C: Compare<T>> Beap<T, C> {
+    // Find the item closest to the target, where distance(target, item) measures how far apart they
+    // are. An item equal to the target is always the closest. Otherwise the closest items on either
+    // side of the target are compared, with tie_break picking one if they are equally far apart
+    // or their distances can't be compared.
+    pub fn find_nearest<D: PartialOrd>(
+        &self,
+        target: &T,
+        distance: impl Fn(&T, &T) -> D,
+        tie_break: TieBreak,
+    ) -> Option<BeapCoordinate> {
+        self.find_nearest_steps::<IgnoreSteps, D>(target, distance, tie_break)
+    }
+    pub fn find_nearest_steps<S: TrackSteps<AnimatedSearch>, D: PartialOrd>(
+        &self,
+        target: &T,
+        distance: impl Fn(&T, &T) -> D,
+        tie_break: TieBreak,
+    ) -> S::WrapOutput<Option<BeapCoordinate>> {
+        let mut steps = S::new();
+        let mut lower: Option<(BeapCoordinate, &T)> = None;
+        let mut upper: Option<(BeapCoordinate, &T)> = None;
+
+        // The walks for the largest item before the target and the smallest item after it take the
+        // same path, so both can be tracked at once, stopping early on an exact match
+        let exact = self.step_through(&mut steps, |coord, value| {
+            let compared = self.order(value, target);
+            match compared {
+                Ordering::Less => {
+                    if lower
+                        .map(|(_, i)| self.order(i, value) == Ordering::Less)
+                        .unwrap_or(true)
+                    {
+                        lower = Some((coord, value));
+                    }
+                }
+                Ordering::Greater => {
+                    if upper
+                        .map(|(_, i)| self.order(i, value) == Ordering::Greater)
+                        .unwrap_or(true)
+                    {
+                        upper = Some((coord, value));
+                    }
+                }
+                Ordering::Equal => {}
+            }
+            compared
+        });
+
+        let nearest = exact.or(match (lower, upper) {
+            (Some((lower, lower_value)), Some((upper, upper_value))) => {
+                let lower_distance = distance(target, lower_value);
+                let upper_distance = distance(target, upper_value);
+                match lower_distance.partial_cmp(&upper_distance) {
+                    Some(Ordering::Less) => Some(lower),
+                    Some(Ordering::Greater) => Some(upper),
+                    _ => match tie_break {
+                        TieBreak::PreferLower => Some(lower),
+                        TieBreak::PreferHigher => Some(upper),
+                    },
+                }
+            }
+            (lower, upper) => lower.or(upper).map(|(coord, _)| coord),
+        });
+
+        steps.wrap_output(nearest)
+    }
+}
@@ -0,0 +1,51 @@
+use std::ops::{Deref, DerefMut};
+
+use super::{animation_util::IgnoreSteps, Beap, BeapCoordinate, Compare};
+
+// Guard for modifying an item of a beap in place, created by Beap::peek_mut and Beap::get_mut.
+// The item is moved up or down to preserve heap property when the guard is dropped.
+pub struct PeekMut<'a, T, C: Compare<T>> {
+    beap: &'a mut Beap<T, C>,
+    coord: BeapCoordinate,
+    // Whether the item was mutably borrowed, as it can only need moving if it was
+    modified: bool,
+}
+
+impl<'a, T, C: Compare<T>> PeekMut<'a, T, C> {
+    pub(super) fn new(beap: &'a mut Beap<T, C>, coord: BeapCoordinate) -> Self {
+        Self {
+            beap,
+            coord,
+            modified: false,
+        }
+    }
+
+    // The coordinate of the item, before it gets moved on drop
+    pub fn coord(this: &Self) -> BeapCoordinate {
+        this.coord
+    }
+}
+
+impl<'a, T, C: Compare<T>> Deref for PeekMut<'a, T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.beap.data[self.coord.array_index()]
+    }
+}
+
+impl<'a, T, C: Compare<T>> DerefMut for PeekMut<'a, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.modified = true;
+        &mut self.beap.data[self.coord.array_index()]
+    }
+}
+
+impl<'a, T, C: Compare<T>> Drop for PeekMut<'a, T, C> {
+    fn drop(&mut self) {
+        if self.modified {
+            self.beap.restore(&mut IgnoreSteps, self.coord);
+            self.beap.debug_validate();
+        }
+    }
+}
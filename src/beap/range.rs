@@ -0,0 +1,67 @@
+use super::BeapCoordinate;
+
+// The run of matching elements within one column of the beap.
+// A column is the chain of left children starting at (pos, pos), i.e. every coordinate with the same
+// pos. Going down a column and going along a row of columns both only ever reach greater values,
+// so the elements matching a range always form a single run in each column.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ColumnSpan {
+    pub(super) pos: usize,
+    // The first and one past the last row offsets (row - pos) of the run
+    pub(super) start: usize,
+    pub(super) end: usize,
+}
+
+impl ColumnSpan {
+    pub(super) fn len(&self) -> usize {
+        self.end - self.start
+    }
+}
+
+// Iterator over every element of a beap within a range, created by Beap::range.
+// Elements are yielded column by column, so not in sorted order.
+#[derive(Debug, Clone)]
+pub struct Range<'a, T> {
+    data: &'a [T],
+    spans: std::vec::IntoIter<ColumnSpan>,
+    current: Option<ColumnSpan>,
+    remaining: usize,
+}
+
+impl<'a, T> Range<'a, T> {
+    pub(super) fn new(data: &'a [T], spans: Vec<ColumnSpan>) -> Self {
+        let remaining = spans.iter().map(ColumnSpan::len).sum();
+        Self {
+            data,
+            spans: spans.into_iter(),
+            current: None,
+            remaining,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = (BeapCoordinate, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Move on to the next non empty span once the current one runs out
+        let span = loop {
+            match self.current.as_mut() {
+                Some(span) if span.start < span.end => break span,
+                _ => self.current = Some(self.spans.next()?),
+            }
+        };
+
+        let coord = BeapCoordinate::new(span.pos + span.start, span.pos);
+        span.start += 1;
+        self.remaining -= 1;
+
+        Some((coord, &self.data[coord.array_index()]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Range<'a, T> {}
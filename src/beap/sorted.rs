@@ -0,0 +1,62 @@
+use super::{Beap, Compare};
+
+// Iterator that pops the items of a beap in order, created by Beap::into_iter_sorted
+#[derive(Debug, Clone)]
+pub struct IntoIterSorted<T, C> {
+    inner: Beap<T, C>,
+}
+
+impl<T, C> IntoIterSorted<T, C> {
+    pub(super) fn new(inner: Beap<T, C>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T, C: Compare<T>> Iterator for IntoIterSorted<T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_top()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, C: Compare<T>> ExactSizeIterator for IntoIterSorted<T, C> {}
+
+// Iterator that pops the items of a beap in order while borrowing it, created by Beap::drain_sorted.
+// Any items that weren't popped are removed when this is dropped.
+#[derive(Debug)]
+pub struct DrainSorted<'a, T, C> {
+    inner: &'a mut Beap<T, C>,
+}
+
+impl<'a, T, C> DrainSorted<'a, T, C> {
+    pub(super) fn new(inner: &'a mut Beap<T, C>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, T, C: Compare<T>> Iterator for DrainSorted<'a, T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_top()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, C: Compare<T>> ExactSizeIterator for DrainSorted<'a, T, C> {}
+
+impl<'a, T, C> Drop for DrainSorted<'a, T, C> {
+    fn drop(&mut self) {
+        self.inner.data.clear();
+    }
+}
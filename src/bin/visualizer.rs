@@ -1,8 +1,3 @@
-mod beap;
-
-#[cfg(test)]
-mod tests;
-
 use std::time::{Duration, Instant};
 
 use beap::{
@@ -122,7 +117,7 @@ impl eframe::App for MyApp {
                     );
                 }
 
-                if ui.button("Remove max").clicked() {
+                if ui.button("Remove min").clicked() {
                     let iter = self
                         .beap
                         .pop_smallest_steps::<StepTracker<_>>()
@@ -135,6 +130,19 @@ impl eframe::App for MyApp {
                     );
                 }
 
+                if ui.button("Remove max").clicked() {
+                    let iter = self
+                        .beap
+                        .pop_largest_steps::<StepTracker<_>>()
+                        .steps
+                        .into_iter();
+
+                    self.current_sequence = SwapAnimationSequence::new(
+                        Box::new(iter),
+                        Duration::from_secs_f32(self.animation_duration),
+                    );
+                }
+
                 if ui.button("Remove random").clicked() {
                     let index = rand::random::<usize>() % self.beap.len();
                     let coord = BeapCoordinate::from_index(index);
@@ -252,9 +260,15 @@ impl eframe::App for MyApp {
             let is_valid_coord =
                 |coord: BeapCoordinate| coord.array_index() < self.last_beap_arr.len();
 
-            for i in 0..self.last_beap_arr.len() {
-                let coord = BeapCoordinate::from_index(i);
+            // The coordinates of the array being drawn, which may be mid animation
+            let coords = || {
+                std::iter::successors(Some(BeapCoordinate::zero()), |coord| {
+                    Some(coord.next_in_order())
+                })
+                .take(self.last_beap_arr.len())
+            };
 
+            for coord in coords() {
                 let draw_child_line = |child: BeapCoordinate| {
                     if is_valid_coord(child) {
                         let child_pos = get_pos_for_coordinate(child);
@@ -269,8 +283,7 @@ impl eframe::App for MyApp {
                 draw_child_line(coord.right_child());
             }
 
-            for i in 0..self.last_beap_arr.len() {
-                let coord = beap::BeapCoordinate::from_index(i);
+            for (coord, value) in coords().zip(&self.last_beap_arr) {
                 let mut pos = get_pos_for_coordinate(coord);
 
                 if let Some(seq) = self.current_sequence.as_ref() {
@@ -309,7 +322,7 @@ impl eframe::App for MyApp {
                 ui.painter().text(
                     pos,
                     Align2::CENTER_CENTER,
-                    self.last_beap_arr[i],
+                    value,
                     FontId::default(),
                     text_col,
                 );
@@ -0,0 +1,9 @@
+//! A bi-parent heap (beap), along with the coordinate system used to address its
+//! nodes and the step tracking used to animate its operations.
+
+mod beap;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::beap::*;
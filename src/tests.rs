@@ -1,2 +1,3 @@
 mod beap;
 mod coords;
+mod indexed;
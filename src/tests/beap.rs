@@ -1,17 +1,50 @@
-use crate::beap::{Beap, BeapCoordinate};
+use std::{
+    cell::Cell,
+    ops::{
+        Bound::{self, Excluded, Included, Unbounded},
+        RangeBounds,
+    },
+};
 
+use rand::Rng;
+
+use crate::beap::{
+    animation_util::{AnimatedSwap, StepTracker},
+    Beap, BeapCoordinate, BeapError, ChildSide, CoordinateError, HeapViolation, MaxBeap, MaxOrder,
+    PeekMut, TieBreak,
+};
+
+// Checks every parent against its children directly, independently of Beap::validate
 fn validate_heap_property(beap: &Beap<i32>) {
-    for (i, node) in beap.iter().enumerate() {
+    validate_min_layout(beap.as_slice());
+}
+
+pub(super) fn validate_min_layout(values: &[i32]) {
+    for (i, node) in values.iter().enumerate() {
         let coord = BeapCoordinate::from_index(i);
-        if let Some(left_child_val) = beap.get_coord(coord.left_child()) {
+        if let Some(left_child_val) = values.get(coord.left_child().array_index()) {
             assert!(node <= left_child_val);
         }
-        if let Some(right_child_val) = beap.get_coord(coord.right_child()) {
+        if let Some(right_child_val) = values.get(coord.right_child().array_index()) {
             assert!(node <= right_child_val);
         }
     }
 }
 
+// Applies the steps to a copy of the array the same way as the visualizer does
+fn replay_steps(values: &mut Vec<i32>, steps: &[AnimatedSwap]) {
+    for step in steps.iter() {
+        let first = step.first.array_index();
+        let second = step.second.array_index();
+        if step.overwrite {
+            values[second] = values[first];
+            values.remove(first);
+        } else {
+            values.swap(first, second);
+        }
+    }
+}
+
 fn make_test_beap() -> Beap<i32> {
     let mut beap = Beap::new();
 
@@ -104,6 +137,54 @@ fn test_random_remove() {
     remove_assert(0);
 }
 
+#[test]
+fn test_remove_bubbles_up_replacement() {
+    // The last item, 3, is smaller than the left parent of the gap it fills, so it has to move up
+    let mut beap = Beap::try_from_vec(vec![0, 1, 10, 2, 11, 12, 3]).unwrap();
+    let gap = BeapCoordinate::from_coords(2, 2).unwrap();
+
+    assert_eq!(beap.remove(gap), Some(12));
+    validate_heap_property(&beap);
+    assert_eq!(beap.into_sorted_vec(), vec![0, 1, 2, 3, 10, 11]);
+}
+
+#[test]
+fn test_random_remove_every_coord() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..100 {
+        let len = rng.gen_range(1..80);
+        let beap: Beap<i32> = (0..len).map(|_| rng.gen_range(0..50)).collect();
+
+        // Remove each coordinate from its own copy of the beap
+        for (coord, &value) in beap.iter_coords() {
+            let mut removed_from = beap.clone();
+            let mut layout = beap.as_slice().to_vec();
+
+            let result = removed_from.remove_steps::<StepTracker<_>>(coord);
+            assert_eq!(result.result, Some(value));
+            validate_heap_property(&removed_from);
+
+            replay_steps(&mut layout, &result.steps);
+            assert_eq!(layout, removed_from.as_slice());
+
+            let mut expected = beap.clone().into_sorted_vec();
+            let index = expected.binary_search(&value).unwrap();
+            expected.remove(index);
+            assert_eq!(removed_from.into_sorted_vec(), expected);
+        }
+
+        // Remove random coordinates until the beap is empty
+        let mut beap = beap;
+        while !beap.is_empty() {
+            let coord = BeapCoordinate::from_index(rng.gen_range(0..beap.len()));
+            let value = beap[coord];
+            assert_eq!(beap.remove(coord), Some(value));
+            validate_heap_property(&beap);
+        }
+    }
+}
+
 #[test]
 fn test_random_increment_decrement() {
     let mut beap = make_test_beap();
@@ -182,3 +263,1274 @@ fn test_find_next_item_greater_index() {
     assert!(beap.find_item(&21).is_none());
     assert!(beap.find_item(&0).is_none());
 }
+
+#[test]
+fn test_find_next_item_greater_or_equal_index() {
+    let beap = make_test_beap();
+
+    let find_next = |value: i32, expected: Option<i32>| {
+        let coord = beap.find_smallest_item_greater_than_or_equal(&value);
+        assert_eq!(
+            coord.and_then(|coord| beap.get_coord(coord)),
+            expected.as_ref()
+        );
+    };
+
+    find_next(0, Some(1));
+    find_next(1, Some(1));
+    find_next(2, Some(2));
+    find_next(6, Some(6));
+    find_next(7, Some(10));
+    find_next(10, Some(10));
+    find_next(16, Some(20));
+    find_next(20, Some(20));
+    find_next(21, None);
+}
+
+#[test]
+fn test_find_previous_item_less_index() {
+    let beap = make_test_beap();
+
+    // Finds the index of the item and verify that it is correct.
+    let find_previous = |value: i32| {
+        // Add 1 so the exact value is found
+        let coord = beap.find_largest_item_less_than(&(value + 1)).unwrap();
+        assert_eq!(beap.get_coord(coord), Some(&value));
+    };
+
+    find_previous(1);
+    find_previous(2);
+    find_previous(3);
+    find_previous(4);
+    find_previous(5);
+    find_previous(6);
+    find_previous(10);
+    find_previous(15);
+    find_previous(20);
+
+    assert_eq!(beap.find_largest_item_less_than(&1), None);
+    let coord = beap.find_largest_item_less_than(&100).unwrap();
+    assert_eq!(beap.get_coord(coord), Some(&20));
+}
+
+#[test]
+fn test_find_previous_item_less_or_equal_index() {
+    let beap = make_test_beap();
+
+    let find_previous = |value: i32, expected: Option<i32>| {
+        let coord = beap.find_largest_item_less_than_or_equal(&value);
+        assert_eq!(
+            coord.and_then(|coord| beap.get_coord(coord)),
+            expected.as_ref()
+        );
+    };
+
+    find_previous(0, None);
+    find_previous(1, Some(1));
+    find_previous(2, Some(2));
+    find_previous(9, Some(6));
+    find_previous(10, Some(10));
+    find_previous(14, Some(10));
+    find_previous(20, Some(20));
+    find_previous(100, Some(20));
+}
+
+#[test]
+fn test_random_neighbour_search() {
+    let mut rng = rand::thread_rng();
+
+    for len in 1..60 {
+        let mut beap = Beap::new();
+        for _ in 0..len {
+            beap.insert(rng.gen_range(0..40));
+        }
+
+        // Compares each search against a linear scan
+        let value_of =
+            |coord: Option<BeapCoordinate>| coord.map(|coord| *beap.get_coord(coord).unwrap());
+        let values: Vec<i32> = beap.iter().copied().collect();
+        for target in -2..42 {
+            assert_eq!(
+                value_of(beap.find_smallest_item_greater_than(&target)),
+                values.iter().copied().filter(|&v| v > target).min()
+            );
+            assert_eq!(
+                value_of(beap.find_smallest_item_greater_than_or_equal(&target)),
+                values.iter().copied().filter(|&v| v >= target).min()
+            );
+            assert_eq!(
+                value_of(beap.find_largest_item_less_than(&target)),
+                values.iter().copied().filter(|&v| v < target).max()
+            );
+            assert_eq!(
+                value_of(beap.find_largest_item_less_than_or_equal(&target)),
+                values.iter().copied().filter(|&v| v <= target).max()
+            );
+        }
+    }
+}
+
+#[test]
+fn test_find_previous_item_steps() {
+    let beap = make_test_beap();
+
+    let result = beap.find_largest_item_less_than_steps::<StepTracker<_>>(&10);
+    assert_eq!(beap.get_coord(result.result.unwrap()), Some(&6));
+
+    // The search starts in the bottom left corner and only walks the staircase
+    assert_eq!(result.steps[0].coord.row(), beap.depth() - 1);
+    assert_eq!(result.steps[0].coord.pos(), 0);
+    assert!(result.steps.len() <= beap.depth() * 2);
+}
+
+#[test]
+fn test_find_nearest() {
+    let beap = make_test_beap();
+    let distance = |a: &i32, b: &i32| (a - b).abs();
+    let nearest = |target: i32, tie_break: TieBreak| {
+        beap.find_nearest(&target, distance, tie_break)
+            .map(|coord| beap[coord])
+    };
+
+    // 1, 1, 2, 3, 4, 5, 6, 10, 10, 15, 20
+    assert_eq!(nearest(5, TieBreak::PreferLower), Some(5));
+    assert_eq!(nearest(8, TieBreak::PreferLower), Some(6));
+    assert_eq!(nearest(9, TieBreak::PreferLower), Some(10));
+    assert_eq!(nearest(8, TieBreak::PreferHigher), Some(10));
+    assert_eq!(nearest(-5, TieBreak::PreferHigher), Some(1));
+    assert_eq!(nearest(100, TieBreak::PreferLower), Some(20));
+
+    // Ties only count when the distances are equal
+    assert_eq!(nearest(0, TieBreak::PreferLower), Some(1));
+    assert_eq!(nearest(12, TieBreak::PreferHigher), Some(10));
+
+    // An exact match ends the walk where it's found
+    let result = beap.find_nearest_steps::<StepTracker<_>, _>(&4, distance, TieBreak::PreferLower);
+    let coord = result.result.unwrap();
+    assert_eq!(beap[coord], 4);
+    assert_eq!(result.steps.last().unwrap().coord, coord);
+
+    assert_eq!(
+        Beap::<i32>::new().find_nearest(&1, distance, TieBreak::PreferLower),
+        None
+    );
+}
+
+#[test]
+fn test_random_find_nearest() {
+    let mut rng = rand::thread_rng();
+    let distance = |a: &i32, b: &i32| (a - b).abs();
+
+    for _ in 0..100 {
+        let beap: Beap<i32> = (0..rng.gen_range(1..60))
+            .map(|_| rng.gen_range(0..100) * 2)
+            .collect();
+        let max_beap: MaxBeap<i32> = beap.iter().copied().collect();
+
+        for target in -5..205 {
+            let lower = beap.iter().copied().filter(|&v| v <= target).max();
+            let upper = beap.iter().copied().filter(|&v| v >= target).min();
+            let closest = |prefer_lower: bool| match (lower, upper) {
+                (Some(lower), Some(upper)) => {
+                    let (lower_distance, upper_distance) = (target - lower, upper - target);
+                    if lower_distance < upper_distance
+                        || (lower_distance == upper_distance && prefer_lower)
+                    {
+                        lower
+                    } else {
+                        upper
+                    }
+                }
+                (lower, upper) => lower.or(upper).unwrap(),
+            };
+
+            let found = beap.find_nearest(&target, distance, TieBreak::PreferLower);
+            assert_eq!(beap[found.unwrap()], closest(true));
+            let found = beap.find_nearest(&target, distance, TieBreak::PreferHigher);
+            assert_eq!(beap[found.unwrap()], closest(false));
+
+            // In a max beap the items ordered before the target are the numerically larger ones
+            let found = max_beap.find_nearest(&target, distance, TieBreak::PreferLower);
+            assert_eq!(max_beap[found.unwrap()], closest(false));
+        }
+    }
+}
+
+#[test]
+fn test_find_all_duplicates() {
+    let beap = make_test_beap();
+
+    // Finds every coordinate of the value and verifies them
+    let find_all = |value: i32| {
+        let coords: Vec<_> = beap.find_all(&value).collect();
+        for coord in coords.iter() {
+            assert_eq!(beap.get_coord(*coord), Some(&value));
+        }
+        assert_eq!(beap.count(&value), coords.len());
+        coords.len()
+    };
+
+    assert_eq!(find_all(1), 2);
+    assert_eq!(find_all(10), 2);
+    assert_eq!(find_all(5), 1);
+    assert_eq!(find_all(20), 1);
+    assert_eq!(find_all(0), 0);
+    assert_eq!(find_all(7), 0);
+    assert_eq!(find_all(21), 0);
+}
+
+#[test]
+fn test_remove_all_duplicates() {
+    let mut beap = make_test_beap();
+
+    assert_eq!(beap.remove_all(&10), vec![10, 10]);
+    validate_heap_property(&beap);
+    assert_eq!(beap.count(&10), 0);
+    assert_eq!(beap.len(), 9);
+
+    assert_eq!(beap.remove_all(&1), vec![1, 1]);
+    validate_heap_property(&beap);
+    assert_eq!(beap.remove_all(&1), vec![]);
+    assert_eq!(beap.len(), 7);
+
+    assert_eq!(beap.pop_smallest(), Some(2));
+}
+
+#[test]
+fn test_random_duplicates() {
+    let mut rng = rand::thread_rng();
+
+    for len in 1..60 {
+        let mut beap = Beap::new();
+        for _ in 0..len {
+            beap.insert(rng.gen_range(0..8));
+        }
+
+        for value in 0..8 {
+            let expected = beap.iter().filter(|&&v| v == value).count();
+            assert_eq!(beap.count(&value), expected);
+            assert_eq!(beap.find_all(&value).count(), expected);
+        }
+
+        let value = rng.gen_range(0..8);
+        let expected = beap.count(&value);
+        assert_eq!(beap.remove_all(&value).len(), expected);
+        validate_heap_property(&beap);
+        assert_eq!(beap.len(), len - expected);
+    }
+}
+
+#[test]
+fn test_max_beap_order() {
+    let mut beap: MaxBeap<i32> = Beap::new_by(MaxOrder);
+    for value in [1, 10, 5, 3, 15, 20, 2, 4, 6, 1, 10] {
+        beap.insert(value);
+    }
+
+    assert_eq!(beap.peek_top(), Some(&20));
+
+    // Searches follow the beap's ordering too, so "greater" means numerically smaller here
+    let coord = beap.find_item(&15).unwrap();
+    assert_eq!(beap.get_coord(coord), Some(&15));
+    let coord = beap.find_smallest_item_greater_than(&10).unwrap();
+    assert_eq!(beap.get_coord(coord), Some(&6));
+
+    // Modifications keep the max ordering
+    let coord = beap.find_item(&2).unwrap();
+    assert_eq!(beap.set_value(coord, 30), Some(2));
+    let coord = beap.find_item(&5).unwrap();
+    assert_eq!(beap.remove(coord), Some(5));
+
+    let mut popped = vec![];
+    while let Some(value) = beap.pop_top() {
+        popped.push(value);
+    }
+    assert_eq!(popped, vec![30, 20, 15, 10, 10, 6, 4, 3, 1, 1]);
+}
+
+#[test]
+fn test_closure_ordering() {
+    // Order by the second field only, so items with equal keys compare as equal
+    let mut beap = Beap::new_by(|a: &(char, i32), b: &(char, i32)| a.1.cmp(&b.1));
+    beap.insert(('a', 3));
+    beap.insert(('b', 1));
+    beap.insert(('c', 2));
+    beap.insert(('d', 5));
+
+    let coord = beap.find_item(&('?', 2)).unwrap();
+    assert_eq!(beap.get_coord(coord), Some(&('c', 2)));
+
+    assert_eq!(beap.pop_top(), Some(('b', 1)));
+    assert_eq!(beap.pop_top(), Some(('c', 2)));
+    assert_eq!(beap.pop_top(), Some(('a', 3)));
+    assert_eq!(beap.pop_top(), Some(('d', 5)));
+    assert_eq!(beap.pop_top(), None);
+
+    let mut reversed = Beap::new_by(|a: &i32, b: &i32| b.cmp(a));
+    reversed.insert(1);
+    reversed.insert(3);
+    reversed.insert(2);
+    assert_eq!(reversed.peek_top(), Some(&3));
+}
+
+#[derive(Debug)]
+struct Task {
+    deadline: u32,
+    name: String,
+}
+
+#[test]
+fn test_search_by_key() {
+    let mut tasks = Beap::new_by(|a: &Task, b: &Task| a.deadline.cmp(&b.deadline));
+    for (deadline, name) in [(30, "c"), (10, "a"), (50, "e"), (20, "b"), (40, "d")] {
+        tasks.insert(Task {
+            deadline,
+            name: name.to_string(),
+        });
+    }
+
+    let coord = tasks.search_by_key(&40, |task| task.deadline).unwrap();
+    assert_eq!(tasks[coord].name, "d");
+    assert_eq!(tasks.search_by_key(&35, |task| task.deadline), None);
+
+    // Searching by a string key that goes up along with the deadlines
+    let coord = tasks
+        .search_by_key(&"b".to_string(), |task| task.name.clone())
+        .unwrap();
+    assert_eq!(tasks[coord].deadline, 20);
+
+    let result = tasks.search_by_key_steps::<StepTracker<_>, _>(&10, |task| task.deadline);
+    assert_eq!(result.result, Some(BeapCoordinate::zero()));
+    assert!(!result.steps.is_empty());
+    assert_eq!(result.steps.last().unwrap().coord, BeapCoordinate::zero());
+
+    let empty = Beap::<Task, _>::new_by(|a: &Task, b: &Task| a.deadline.cmp(&b.deadline));
+    assert_eq!(empty.search_by_key(&10, |task| task.deadline), None);
+}
+
+#[test]
+fn test_random_search_by() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..100 {
+        let beap: Beap<i32> = (0..rng.gen_range(0..100))
+            .map(|_| rng.gen_range(0..200))
+            .collect();
+        let max_beap: MaxBeap<i32> = beap.iter().copied().collect();
+
+        for target in 0..200 {
+            let contains = beap.iter().any(|&value| value / 10 == target / 10);
+
+            // Matching on a coarser key, so that many items are equal to the target
+            let found = beap.search_by(|value| (value / 10).cmp(&(target / 10)));
+            assert_eq!(found.is_some(), contains);
+            if let Some(coord) = found {
+                assert_eq!(beap[coord] / 10, target / 10);
+            }
+
+            // A max beap gets searched with the comparison the other way around
+            let found = max_beap.search_by(|value| (target / 10).cmp(&(value / 10)));
+            assert_eq!(found.is_some(), contains);
+            if let Some(coord) = found {
+                assert_eq!(max_beap[coord] / 10, target / 10);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_range_query() {
+    let beap = make_test_beap();
+
+    // Collects the values found by the range query, checking the coordinates on the way
+    let range = |range: (Bound<i32>, Bound<i32>)| {
+        let found = beap.range(range);
+        assert_eq!(found.len(), found.clone().count());
+
+        let mut values: Vec<_> = found
+            .map(|(coord, value)| {
+                assert_eq!(beap.get_coord(coord), Some(value));
+                *value
+            })
+            .collect();
+        values.sort();
+
+        let mut expected: Vec<_> = beap.iter().copied().filter(|v| range.contains(v)).collect();
+        expected.sort();
+        assert_eq!(values, expected);
+
+        values
+    };
+
+    assert_eq!(range((Included(2), Included(6))), vec![2, 3, 4, 5, 6]);
+    assert_eq!(range((Excluded(1), Excluded(10))), vec![2, 3, 4, 5, 6]);
+    assert_eq!(range((Included(10), Unbounded)), vec![10, 10, 15, 20]);
+    assert_eq!(range((Unbounded, Included(1))), vec![1, 1]);
+    assert_eq!(range((Unbounded, Unbounded)).len(), beap.len());
+    assert_eq!(range((Included(7), Included(9))), vec![]);
+    assert_eq!(range((Included(21), Unbounded)), vec![]);
+    assert_eq!(range((Excluded(20), Unbounded)), vec![]);
+    assert_eq!(range((Unbounded, Excluded(1))), vec![]);
+
+    // Also works through the std range types
+    assert_eq!(beap.range(3..5).count(), 2);
+    assert_eq!(beap.range(..=10).count(), 9);
+    assert_eq!(Beap::<i32>::new().range(..).count(), 0);
+}
+
+#[test]
+fn test_random_range_query() {
+    let mut rng = rand::thread_rng();
+
+    for len in 0..60 {
+        let mut beap = Beap::new();
+        for _ in 0..len {
+            beap.insert(rng.gen_range(0..40));
+        }
+
+        for _ in 0..20 {
+            let start = rng.gen_range(-5..45);
+            let end = rng.gen_range(start..50);
+
+            let mut found: Vec<_> = beap.range(start..end).map(|(_, v)| *v).collect();
+            found.sort();
+            let mut expected: Vec<_> = beap
+                .iter()
+                .copied()
+                .filter(|v| (start..end).contains(v))
+                .collect();
+            expected.sort();
+            assert_eq!(found, expected);
+        }
+    }
+}
+
+#[test]
+fn test_range_query_steps() {
+    let beap = make_test_beap();
+
+    let result = beap.range_steps::<StepTracker<_>, _>(2..=6);
+    assert_eq!(result.result.count(), 5);
+
+    // The search visits less than the whole beap, and starts in the bottom left corner
+    assert!(!result.steps.is_empty());
+    assert!(result.steps.len() < beap.len() * 2);
+    assert_eq!(result.steps[0].coord.row(), beap.depth() - 1);
+    assert_eq!(result.steps[0].coord.pos(), 0);
+}
+
+#[test]
+fn test_rank_queries() {
+    let beap = make_test_beap();
+
+    // 1, 1, 2, 3, 4, 5, 6, 10, 10, 15, 20
+    assert_eq!(beap.count_less_than(&1), 0);
+    assert_eq!(beap.count_less_than(&2), 2);
+    assert_eq!(beap.count_less_than(&10), 7);
+    assert_eq!(beap.count_less_than(&100), 11);
+    assert_eq!(beap.count_in_range(3..=10), 6);
+    assert_eq!(beap.count_in_range(11..15), 0);
+    assert_eq!(beap.count_in_range(..), 11);
+
+    let sorted = [1, 1, 2, 3, 4, 5, 6, 10, 10, 15, 20];
+    for (k, &expected) in sorted.iter().enumerate() {
+        assert_eq!(beap[beap.nth_smallest(k).unwrap()], expected);
+    }
+    assert_eq!(beap.nth_smallest(11), None);
+
+    // Finding the smallest is a single step at the top
+    let result = beap.nth_smallest_steps::<StepTracker<_>>(0);
+    assert_eq!(result.result, Some(BeapCoordinate::zero()));
+    assert_eq!(result.steps.len(), 1);
+
+    let empty = Beap::<i32>::new();
+    assert_eq!(empty.count_less_than(&1), 0);
+    assert_eq!(empty.count_in_range(..), 0);
+    assert_eq!(empty.nth_smallest(0), None);
+}
+
+#[test]
+fn test_random_rank_queries() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..50 {
+        let beap: Beap<i32> = (0..rng.gen_range(0..100))
+            .map(|_| rng.gen_range(0..50))
+            .collect();
+        let max_beap: MaxBeap<i32> = beap.iter().copied().collect();
+        let mut sorted = beap.as_slice().to_vec();
+        sorted.sort_unstable();
+
+        for value in -1..51 {
+            let less = sorted.iter().filter(|&&v| v < value).count();
+            assert_eq!(beap.count_less_than(&value), less);
+            assert_eq!(
+                beap.count_in_range(value..value + 10),
+                sorted
+                    .iter()
+                    .filter(|&&v| v >= value && v < value + 10)
+                    .count()
+            );
+
+            // Less than in a max beap means numerically greater
+            let greater = sorted.iter().filter(|&&v| v > value).count();
+            assert_eq!(max_beap.count_less_than(&value), greater);
+        }
+
+        // The counting only visits the ends of the runs, not every item in range
+        let steps = beap
+            .count_in_range_steps::<StepTracker<_>, _>(..)
+            .steps
+            .len();
+        assert!(steps <= 2 * beap.depth() + 2);
+
+        for (k, &expected) in sorted.iter().enumerate() {
+            assert_eq!(beap[beap.nth_smallest(k).unwrap()], expected);
+            assert_eq!(
+                max_beap[max_beap.nth_smallest(k).unwrap()],
+                sorted[sorted.len() - 1 - k]
+            );
+        }
+        assert_eq!(beap.nth_smallest(sorted.len()), None);
+    }
+}
+
+#[test]
+fn test_from_vec() {
+    let values = vec![20, 15, 10, 10, 6, 5, 4, 3, 2, 1, 1];
+    let mut beap = Beap::from_vec(values.clone());
+    validate_heap_property(&beap);
+
+    let mut expected = values;
+    expected.sort();
+    for value in expected {
+        assert_eq!(beap.pop_smallest(), Some(value));
+    }
+    assert_eq!(beap.pop_smallest(), None);
+
+    assert!(Beap::<i32>::from_vec(vec![]).is_empty());
+    assert_eq!(Beap::from_vec(vec![1]).peek_top(), Some(&1));
+
+    // A sorted vector is already laid out as a beap
+    let mut rng = rand::thread_rng();
+    let mut sorted: Vec<i32> = (0..200).map(|_| rng.gen_range(0..100)).collect();
+    sorted.sort();
+    assert!(Beap::try_from_vec(sorted).is_ok());
+}
+
+#[test]
+fn test_random_from_iter_and_extend() {
+    let mut rng = rand::thread_rng();
+
+    for len in 0..80 {
+        let values: Vec<i32> = (0..len).map(|_| rng.gen_range(0..50)).collect();
+
+        let beap: Beap<i32> = values.iter().copied().collect();
+        validate_heap_property(&beap);
+        assert_eq!(beap.len(), len);
+
+        let mut beap = Beap::from(values.clone());
+        validate_heap_property(&beap);
+
+        beap.extend(values.iter().map(|v| v * 2));
+        validate_heap_property(&beap);
+        assert_eq!(beap.len(), len * 2);
+    }
+}
+
+#[test]
+fn test_from_vec_steps() {
+    let values = vec![20, 15, 10, 10, 6, 5, 4, 3, 2, 1, 1];
+    let result = Beap::from_vec_steps::<StepTracker<_>>(values.clone());
+
+    // Replaying the swaps on the original array gives the same layout as the built beap
+    let mut replayed = values;
+    for step in result.steps.iter() {
+        assert!(!step.overwrite);
+        replayed.swap(step.first.array_index(), step.second.array_index());
+    }
+    assert_eq!(replayed, result.result.iter().copied().collect::<Vec<_>>());
+    validate_heap_property(&result.result);
+}
+
+#[test]
+fn test_from_vec_by_steps() {
+    let values = vec![1, 1, 2, 3, 4, 5, 6, 10, 10, 15, 20];
+    let result = Beap::from_vec_by_steps::<StepTracker<_>>(values.clone(), MaxOrder);
+
+    // Same as for from_vec_steps, but the largest item ends up at the top
+    let mut replayed = values.clone();
+    replay_steps(&mut replayed, &result.steps);
+    assert_eq!(replayed, result.result.as_slice());
+    assert_eq!(result.result.peek_top(), Some(&20));
+
+    // Building without steps sorts instead, ending up with the same items in a different layout
+    let sorted = MaxBeap::from_vec_by(values, MaxOrder);
+    assert_eq!(sorted.as_slice(), &[20, 15, 10, 10, 6, 5, 4, 3, 2, 1, 1]);
+    assert_eq!(sorted, result.result);
+}
+
+#[test]
+fn test_sorted_consumption() {
+    let mut rng = rand::thread_rng();
+
+    for len in 0..60 {
+        let values: Vec<i32> = (0..len).map(|_| rng.gen_range(0..30)).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+
+        let beap = Beap::from_vec(values.clone());
+        assert_eq!(beap.clone().into_sorted_vec(), sorted);
+
+        let iter = beap.clone().into_iter_sorted();
+        assert_eq!(iter.len(), len);
+        assert_eq!(iter.collect::<Vec<_>>(), sorted);
+
+        let mut beap = beap;
+        assert_eq!(beap.drain_sorted().collect::<Vec<_>>(), sorted);
+        assert!(beap.is_empty());
+
+        // Max beaps come out from largest to smallest
+        let max_beap: MaxBeap<i32> = values.into_iter().collect();
+        sorted.reverse();
+        assert_eq!(max_beap.into_sorted_vec(), sorted);
+    }
+}
+
+#[test]
+fn test_drain_sorted_partially() {
+    let mut beap = make_test_beap();
+
+    let mut drain = beap.drain_sorted();
+    assert_eq!(drain.next(), Some(1));
+    assert_eq!(drain.next(), Some(1));
+    assert_eq!(drain.len(), 9);
+    drop(drain);
+
+    // The rest is removed on drop
+    assert!(beap.is_empty());
+    beap.insert(5);
+    assert_eq!(beap.into_iter_sorted().next(), Some(5));
+}
+
+#[test]
+fn test_pop_largest_order() {
+    let mut beap = make_test_beap();
+
+    assert_eq!(beap.peek_largest(), Some(&20));
+    assert_eq!(beap.pop_largest(), Some(20));
+    validate_heap_property(&beap);
+    assert_eq!(beap.pop_largest(), Some(15));
+    validate_heap_property(&beap);
+    assert_eq!(beap.pop_largest(), Some(10));
+    validate_heap_property(&beap);
+
+    // Works from both ends at once
+    assert_eq!(beap.pop_smallest(), Some(1));
+    validate_heap_property(&beap);
+    assert_eq!(beap.pop_largest(), Some(10));
+    validate_heap_property(&beap);
+    assert_eq!(beap.peek_largest(), Some(&6));
+
+    let mut rest = vec![];
+    while let Some(value) = beap.pop_largest() {
+        validate_heap_property(&beap);
+        rest.push(value);
+    }
+    assert_eq!(rest, vec![6, 5, 4, 3, 2, 1]);
+    assert_eq!(beap.peek_largest(), None);
+}
+
+#[test]
+fn test_random_pop_largest() {
+    let mut rng = rand::thread_rng();
+
+    for len in 0..60 {
+        let values: Vec<i32> = (0..len).map(|_| rng.gen_range(0..30)).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+
+        let mut beap = Beap::from_vec(values);
+        while let Some(largest) = sorted.pop() {
+            assert_eq!(beap.peek_largest(), Some(&largest));
+            assert_eq!(beap.pop_largest(), Some(largest));
+            validate_heap_property(&beap);
+        }
+        assert_eq!(beap.pop_largest(), None);
+    }
+}
+
+#[test]
+fn test_pop_largest_steps() {
+    let mut beap = make_test_beap();
+    let mut replayed: Vec<_> = beap.iter().copied().collect();
+
+    let result = beap.pop_largest_steps::<StepTracker<_>>();
+    assert_eq!(result.result, Some(20));
+
+    replay_steps(&mut replayed, &result.steps);
+    assert_eq!(replayed, beap.iter().copied().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_append_and_merge() {
+    let mut beap = make_test_beap();
+    let mut other = Beap::from_vec(vec![7, 0, 30]);
+
+    beap.append(&mut other);
+    validate_heap_property(&beap);
+    assert!(other.is_empty());
+    assert_eq!(beap.len(), 14);
+    assert_eq!(beap.peek_top(), Some(&0));
+    assert_eq!(beap.peek_largest(), Some(&30));
+
+    let merged = Beap::merge(Beap::from_vec(vec![3, 1]), Beap::from_vec(vec![2, 4, 0]));
+    validate_heap_property(&merged);
+    assert_eq!(merged.into_sorted_vec(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn test_append_keeps_own_ordering() {
+    // Both beaps have the same closure type, but only the first one keeps the smallest at the top
+    let ordering = |reversed: bool| {
+        move |a: &i32, b: &i32| {
+            if reversed {
+                b.cmp(a)
+            } else {
+                a.cmp(b)
+            }
+        }
+    };
+
+    let mut min_beap = Beap::from_vec_by(vec![5, 3], ordering(false));
+    let mut max_beap = Beap::from_vec_by((0..10).collect(), ordering(true));
+    assert_eq!(max_beap.peek_top(), Some(&9));
+
+    min_beap.append(&mut max_beap);
+    assert!(max_beap.is_empty());
+    validate_min_layout(min_beap.as_slice());
+    assert_eq!(min_beap.peek_top(), Some(&0));
+    assert_eq!(
+        min_beap.into_sorted_vec(),
+        vec![0, 1, 2, 3, 3, 4, 5, 5, 6, 7, 8, 9]
+    );
+
+    // The emptied beap keeps its own ordering too
+    max_beap.extend([1, 4, 2]);
+    assert_eq!(max_beap.peek_top(), Some(&4));
+}
+
+#[test]
+fn test_random_append_and_merge() {
+    let mut rng = rand::thread_rng();
+
+    // Covers both appending a few items, and appending enough to rebuild
+    for _ in 0..300 {
+        let a_len = rng.gen_range(0..60);
+        let b_len = rng.gen_range(0..60);
+        let a_values: Vec<i32> = (0..a_len).map(|_| rng.gen_range(0..100)).collect();
+        let b_values: Vec<i32> = (0..b_len).map(|_| rng.gen_range(0..100)).collect();
+
+        let mut expected: Vec<_> = a_values.iter().chain(b_values.iter()).copied().collect();
+        expected.sort();
+
+        let mut a = Beap::from_vec(a_values.clone());
+        let mut b = Beap::from_vec(b_values.clone());
+        a.append(&mut b);
+        validate_heap_property(&a);
+        validate_heap_property(&b);
+        assert!(b.is_empty());
+        assert_eq!(a.into_sorted_vec(), expected);
+
+        let merged = Beap::merge(
+            Beap::from_vec(a_values.clone()),
+            Beap::from_vec(b_values.clone()),
+        );
+        validate_heap_property(&merged);
+        assert_eq!(merged.into_sorted_vec(), expected);
+
+        let mut extended = Beap::from_vec(a_values);
+        extended.extend(b_values);
+        validate_heap_property(&extended);
+        assert_eq!(extended.into_sorted_vec(), expected);
+    }
+}
+
+#[test]
+fn test_retain_and_extract_if() {
+    let mut beap = make_test_beap();
+
+    beap.retain(|&value| value != 10);
+    validate_heap_property(&beap);
+    assert_eq!(beap.len(), 9);
+    assert_eq!(beap.count(&10), 0);
+
+    let mut extracted: Vec<_> = beap.extract_if(|&value| value % 2 == 0).collect();
+    extracted.sort();
+    assert_eq!(extracted, vec![2, 4, 6, 20]);
+    validate_heap_property(&beap);
+    assert_eq!(beap.into_sorted_vec(), vec![1, 1, 3, 5, 15]);
+}
+
+#[test]
+fn test_random_retain_and_extract_if() {
+    let mut rng = rand::thread_rng();
+
+    for len in 0..80 {
+        let values: Vec<i32> = (0..len).map(|_| rng.gen_range(0..50)).collect();
+        let cutoff = rng.gen_range(0..50);
+
+        let mut kept: Vec<_> = values.iter().copied().filter(|&v| v < cutoff).collect();
+        kept.sort();
+        let mut removed: Vec<_> = values.iter().copied().filter(|&v| v >= cutoff).collect();
+        removed.sort();
+
+        let mut beap = Beap::from_vec(values.clone());
+        beap.retain(|&value| value < cutoff);
+        validate_heap_property(&beap);
+        assert_eq!(beap.into_sorted_vec(), kept);
+
+        let mut beap = Beap::from_vec(values);
+        let mut extracted: Vec<_> = beap.extract_if(|&value| value >= cutoff).collect();
+        extracted.sort();
+        validate_heap_property(&beap);
+        assert_eq!(extracted, removed);
+        assert_eq!(beap.into_sorted_vec(), kept);
+    }
+}
+
+#[test]
+fn test_retain_steps() {
+    let mut beap = make_test_beap();
+    let mut replayed: Vec<_> = beap.iter().copied().collect();
+
+    let steps = beap
+        .retain_steps::<StepTracker<_>>(|&value| value > 3)
+        .steps;
+
+    replay_steps(&mut replayed, &steps);
+    assert_eq!(replayed, beap.iter().copied().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_append_steps() {
+    // Appending a smaller beap bubbles its items up, appending a larger one rebuilds everything
+    for (first, second) in [
+        (vec![1, 10, 5, 3, 15, 20, 2, 4, 6], vec![7, 0]),
+        (vec![5], vec![0, 1, 2, 3, 4, 6]),
+    ] {
+        let mut beap = Beap::from_vec(first);
+        let mut other = Beap::from_vec(second);
+        let mut replayed: Vec<_> = beap.iter().chain(other.iter()).copied().collect();
+
+        let steps = beap.append_steps::<StepTracker<_>>(&mut other).steps;
+
+        replay_steps(&mut replayed, &steps);
+        assert_eq!(replayed, beap.iter().copied().collect::<Vec<_>>());
+        validate_heap_property(&beap);
+        assert!(other.is_empty());
+    }
+}
+
+#[test]
+fn test_peek_mut() {
+    let mut beap = make_test_beap();
+
+    // Increasing the top makes it sink
+    *beap.peek_mut().unwrap() += 100;
+    validate_heap_property(&beap);
+    assert_eq!(beap.peek_top(), Some(&1));
+    assert_eq!(beap.peek_largest(), Some(&101));
+
+    // Only reading through the guard doesn't move anything
+    let layout: Vec<_> = beap.iter().copied().collect();
+    assert_eq!(*beap.peek_mut().unwrap(), 1);
+    assert_eq!(beap.iter().copied().collect::<Vec<_>>(), layout);
+
+    assert!(Beap::<i32>::new().peek_mut().is_none());
+}
+
+#[test]
+fn test_get_mut_and_modify() {
+    let mut beap = make_test_beap();
+
+    // Decreasing an item makes it bubble up
+    let coord = beap.find_item(&15).unwrap();
+    {
+        let mut value = beap.get_mut(coord).unwrap();
+        assert_eq!(PeekMut::coord(&value), coord);
+        *value = 0;
+    }
+    validate_heap_property(&beap);
+    assert_eq!(beap.peek_top(), Some(&0));
+
+    // Modifying returns where the item ended up
+    let coord = beap.find_item(&2).unwrap();
+    let moved = beap.modify(coord, |value| *value = 50).unwrap();
+    validate_heap_property(&beap);
+    assert_eq!(beap.get_coord(moved), Some(&50));
+
+    let coord = beap.find_item(&20).unwrap();
+    let moved = beap.modify(coord, |value| *value = -1).unwrap();
+    validate_heap_property(&beap);
+    assert_eq!(moved, BeapCoordinate::zero());
+
+    let out_of_bounds = BeapCoordinate::from_index(beap.len());
+    assert!(beap.get_mut(out_of_bounds).is_none());
+    assert_eq!(beap.modify(out_of_bounds, |value| *value = 0), None);
+}
+
+#[test]
+fn test_random_modify() {
+    let mut rng = rand::thread_rng();
+
+    for len in 1..60 {
+        let mut beap = Beap::from_vec((0..len).map(|_| rng.gen_range(0..50)).collect());
+
+        for _ in 0..20 {
+            let coord = BeapCoordinate::from_index(rng.gen_range(0..len));
+            let new_value = rng.gen_range(0..50);
+
+            if rng.gen() {
+                let moved = beap.modify(coord, |value| *value = new_value).unwrap();
+                assert_eq!(beap.get_coord(moved), Some(&new_value));
+            } else {
+                *beap.get_mut(coord).unwrap() = new_value;
+            }
+            validate_heap_property(&beap);
+        }
+    }
+}
+
+#[test]
+fn test_collection_traits() {
+    let beap = make_test_beap();
+
+    // Equality ignores the layout
+    let rebuilt = Beap::from_vec(vec![20, 15, 10, 10, 6, 5, 4, 3, 2, 1, 1]);
+    assert_ne!(
+        beap.iter().collect::<Vec<_>>(),
+        rebuilt.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(beap, rebuilt);
+    assert_ne!(
+        beap,
+        Beap::from_vec(vec![20, 15, 10, 10, 6, 5, 4, 3, 2, 1, 2])
+    );
+    assert_ne!(beap, Beap::from_vec(vec![20, 15, 10, 10, 6, 5, 4, 3, 2, 1]));
+    assert_eq!(Beap::<i32>::default(), Beap::new());
+
+    // Indexing by coordinate
+    for (i, value) in beap.iter().enumerate() {
+        assert_eq!(&beap[BeapCoordinate::from_index(i)], value);
+    }
+
+    // Iterating by reference and by value both follow the layout
+    let layout: Vec<i32> = Vec::from(beap.clone());
+    assert_eq!((&beap).into_iter().copied().collect::<Vec<_>>(), layout);
+    let mut total = 0;
+    for value in &beap {
+        total += value;
+    }
+    assert_eq!(total, layout.iter().sum());
+    assert_eq!(beap.clone().into_iter().collect::<Vec<_>>(), layout);
+
+    // Extending by reference
+    let mut extended = Beap::new();
+    extended.extend(&layout);
+    validate_heap_property(&extended);
+    assert_eq!(extended, beap);
+}
+
+#[test]
+#[should_panic]
+fn test_index_out_of_bounds() {
+    let beap = make_test_beap();
+    let _ = beap[BeapCoordinate::from_index(beap.len())];
+}
+
+#[test]
+#[should_panic(expected = "invalid coordinate")]
+fn test_index_huge_coordinate() {
+    // The array index of this coordinate overflows, so it mustn't wrap around to an item
+    let beap = make_test_beap();
+    let _ = beap[BeapCoordinate::from_coords(usize::MAX, 1).unwrap()];
+}
+
+#[test]
+fn test_validate_reports_violation() {
+    // Items are ordered by a key that can be changed behind the beap's back, breaking its layout
+    let keys: Vec<Cell<i32>> = (0..6).map(Cell::new).collect();
+    let mut beap = Beap::new_by(|a: &usize, b: &usize| keys[*a].get().cmp(&keys[*b].get()));
+    beap.extend(0..6);
+    assert_eq!(beap.validate(), Ok(()));
+
+    // Make the right child of the root smaller than it
+    let right = *beap.get_coord(BeapCoordinate::from_index(2)).unwrap();
+    keys[right].set(-1);
+    let violation = beap.validate().unwrap_err();
+    assert_eq!(
+        violation,
+        HeapViolation {
+            parent: BeapCoordinate::zero(),
+            child: BeapCoordinate::from_index(2),
+            side: ChildSide::Right,
+        }
+    );
+    assert_eq!(
+        violation.to_string(),
+        "heap property violated: the right child at (1, 1) is smaller than its parent at (0, 0)"
+    );
+
+    // A left child further down
+    keys[right].set(2);
+    let left = *beap.get_coord(BeapCoordinate::from_index(3)).unwrap();
+    keys[left].set(0);
+    assert_eq!(
+        beap.validate(),
+        Err(HeapViolation {
+            parent: BeapCoordinate::from_index(1),
+            child: BeapCoordinate::from_index(3),
+            side: ChildSide::Left,
+        })
+    );
+}
+
+#[test]
+fn test_raw_vec_round_trip() {
+    let beap = make_test_beap();
+    let layout = beap.as_slice().to_vec();
+    assert_eq!(layout, beap.iter().copied().collect::<Vec<_>>());
+
+    // Reloading keeps the exact layout
+    let reloaded = Beap::try_from_vec(beap.clone().into_vec()).unwrap();
+    assert_eq!(reloaded.as_slice(), &layout[..]);
+    validate_heap_property(&reloaded);
+
+    let trusted = Beap::from_vec_unchecked(layout.clone());
+    assert_eq!(trusted.as_slice(), &layout[..]);
+    assert_eq!(trusted.into_sorted_vec(), beap.into_sorted_vec());
+
+    // Each item is addressed by its array index
+    for (index, value) in layout.iter().enumerate() {
+        assert_eq!(
+            reloaded.get_coord(BeapCoordinate::from_index(index)),
+            Some(value)
+        );
+    }
+
+    // Max beaps are checked with their own ordering
+    let max_layout = vec![5, 4, 3, 1, 2];
+    assert!(Beap::try_from_vec(max_layout.clone()).is_err());
+    let max_beap = Beap::try_from_vec_by(max_layout, MaxOrder).unwrap();
+    assert_eq!(max_beap.peek_top(), Some(&5));
+}
+
+#[test]
+fn test_try_from_invalid_vec() {
+    assert_eq!(
+        Beap::try_from_vec(vec![1, 2, 0]).unwrap_err(),
+        HeapViolation {
+            parent: BeapCoordinate::zero(),
+            child: BeapCoordinate::from_index(2),
+            side: ChildSide::Right,
+        }
+    );
+    assert_eq!(
+        Beap::try_from_vec(vec![1, 2, 3, 1]).unwrap_err(),
+        HeapViolation {
+            parent: BeapCoordinate::from_index(1),
+            child: BeapCoordinate::from_index(3),
+            side: ChildSide::Left,
+        }
+    );
+    assert!(Beap::<i32>::try_from_vec(vec![]).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn test_with_depth_overflow() {
+    // The row count times the next one overflows, even though half of it doesn't
+    let _ = Beap::<u8>::with_depth(usize::MAX);
+}
+
+#[test]
+fn test_capacity_management() {
+    // Filling a beap to its depth doesn't reallocate
+    let mut beap = Beap::with_depth(5);
+    let capacity = beap.capacity();
+    assert!(capacity >= 15);
+    let data_ptr = beap.as_slice().as_ptr();
+    for value in (0..15).rev() {
+        beap.insert(value);
+    }
+    assert_eq!(beap.depth(), 5);
+    assert_eq!(beap.capacity(), capacity);
+    assert_eq!(beap.as_slice().as_ptr(), data_ptr);
+    validate_heap_property(&beap);
+
+    // Clearing keeps the space around
+    beap.clear();
+    assert!(beap.is_empty());
+    assert_eq!(beap.capacity(), capacity);
+    assert_eq!(beap.pop_smallest(), None);
+
+    beap.shrink_to_fit();
+    assert_eq!(beap.capacity(), 0);
+
+    beap.reserve(10);
+    assert!(beap.capacity() >= 10);
+
+    let mut beap = Beap::with_capacity(3);
+    assert!(beap.capacity() >= 3);
+    beap.extend([3, 1, 2]);
+    assert_eq!(beap.peek_top(), Some(&1));
+
+    let beap: MaxBeap<i32> = Beap::with_depth_by(2, MaxOrder);
+    assert!(beap.capacity() >= 3);
+    assert_eq!(Beap::<i32>::with_depth(0).capacity(), 0);
+}
+
+#[test]
+fn test_rows_and_coords() {
+    let beap = make_test_beap();
+    let layout = beap.as_slice();
+
+    // 11 items fill four rows and one item of the fifth
+    let row_lens = beap.rows().map(|row| row.len()).collect::<Vec<_>>();
+    assert_eq!(row_lens, vec![1, 2, 3, 4, 1]);
+    assert_eq!(
+        beap.rows().flatten().collect::<Vec<_>>(),
+        beap.iter().collect::<Vec<_>>()
+    );
+
+    assert_eq!(beap.row(0), Some(&layout[0..1]));
+    assert_eq!(beap.row(2), Some(&layout[3..6]));
+    assert_eq!(beap.row(4), Some(&layout[10..11]));
+    assert_eq!(beap.row(5), None);
+    assert_eq!(beap.row(usize::MAX), None);
+
+    assert_eq!(beap.iter_coords().count(), beap.len());
+    for (coord, value) in beap.iter_coords() {
+        assert_eq!(beap.get_coord(coord), Some(value));
+        assert_eq!(beap.row(coord.row()).unwrap()[coord.pos()], *value);
+    }
+
+    let empty = Beap::<i32>::new();
+    assert_eq!(empty.rows().count(), 0);
+    assert_eq!(empty.row(0), None);
+    assert_eq!(empty.iter_coords().count(), 0);
+}
+
+#[test]
+fn test_empty_beap_edge_cases() {
+    let mut beap = Beap::<i32>::new();
+    let zero = BeapCoordinate::zero();
+
+    // Searches don't have anywhere to start from
+    assert_eq!(beap.find_item(&1), None);
+    assert_eq!(beap.find_item_steps::<StepTracker<_>>(&1).steps.len(), 0);
+    assert_eq!(beap.find_all(&1).len(), 0);
+    assert_eq!(beap.count(&1), 0);
+    assert_eq!(beap.find_smallest_item_greater_than(&1), None);
+    assert_eq!(beap.find_smallest_item_greater_than_or_equal(&1), None);
+    assert_eq!(beap.find_largest_item_less_than(&1), None);
+    assert_eq!(beap.find_largest_item_less_than_or_equal(&1), None);
+    assert_eq!(beap.range(..).len(), 0);
+    assert_eq!(beap.remove_all(&1), vec![]);
+
+    // Reading and modifying
+    assert_eq!(beap.peek_top(), None);
+    assert_eq!(beap.peek_largest(), None);
+    assert_eq!(beap.get_coord(zero), None);
+    assert!(beap.peek_mut().is_none());
+    assert!(beap.get_mut(zero).is_none());
+    assert_eq!(beap.modify(zero, |value| *value += 1), None);
+    assert_eq!(beap.set_value(zero, 1), None);
+    assert_eq!(beap.remove(zero), None);
+    assert_eq!(beap.pop_top(), None);
+    assert_eq!(beap.pop_largest(), None);
+    assert_eq!(beap.depth(), 0);
+    assert_eq!(beap.validate(), Ok(()));
+
+    // The try_ versions say why
+    let out_of_bounds = BeapError::CoordinateOutOfBounds {
+        coord: zero,
+        len: 0,
+    };
+    assert_eq!(beap.try_peek_top(), Err(BeapError::Empty));
+    assert_eq!(beap.try_peek_largest(), Err(BeapError::Empty));
+    assert_eq!(beap.try_pop_top(), Err(BeapError::Empty));
+    assert_eq!(beap.try_pop_largest(), Err(BeapError::Empty));
+    assert_eq!(beap.try_peek_mut().err(), Some(BeapError::Empty));
+    assert_eq!(beap.try_get_mut(zero).err(), Some(out_of_bounds));
+    assert_eq!(beap.try_get_coord(zero), Err(out_of_bounds));
+    assert_eq!(beap.try_set_value(zero, 1), Err(out_of_bounds));
+    assert_eq!(beap.try_modify(zero, |_| {}), Err(out_of_bounds));
+    assert_eq!(beap.try_remove(zero), Err(out_of_bounds));
+
+    // Emptying a beap leaves it usable
+    beap.insert(1);
+    assert_eq!(beap.try_pop_top(), Ok(1));
+    assert_eq!(beap.find_item(&1), None);
+    assert!(beap.drain_sorted().next().is_none());
+    assert_eq!(beap.into_sorted_vec(), vec![]);
+}
+
+#[test]
+fn test_try_operations() {
+    let mut beap = make_test_beap();
+    let len = beap.len();
+
+    let out_of_bounds = BeapCoordinate::from_index(len);
+    assert_eq!(
+        beap.try_remove(out_of_bounds),
+        Err(BeapError::CoordinateOutOfBounds {
+            coord: out_of_bounds,
+            len
+        })
+    );
+    assert_eq!(beap.remove(out_of_bounds), None);
+
+    // A coordinate whose array index doesn't fit in a usize
+    let past_max = BeapCoordinate::from_coords(BeapCoordinate::MAX_ROW + 1, 0).unwrap();
+    let invalid = BeapError::InvalidCoordinate(CoordinateError::RowTooLarge {
+        row: BeapCoordinate::MAX_ROW + 1,
+    });
+    assert_eq!(beap.try_get_coord(past_max), Err(invalid));
+    assert_eq!(beap.try_set_value(past_max, 0), Err(invalid));
+    assert_eq!(beap.get_coord(past_max), None);
+    let huge = BeapCoordinate::from_coords(usize::MAX, usize::MAX).unwrap();
+    assert_eq!(beap.remove(huge), None);
+    assert_eq!(beap.len(), len);
+
+    // In bounds coordinates behave like the Option versions
+    let top = BeapCoordinate::zero();
+    assert_eq!(beap.try_get_coord(top), Ok(&1));
+    assert_eq!(beap.try_set_value(top, 7), Ok(1));
+    validate_heap_property(&beap);
+    let coord = beap.find_item(&7).unwrap();
+    let moved = beap.try_modify(coord, |value| *value = 0).unwrap();
+    assert_eq!(moved, top);
+    assert_eq!(beap.try_remove(top), Ok(0));
+    assert_eq!(beap.try_peek_largest(), Ok(&20));
+    assert_eq!(beap.try_pop_largest(), Ok(20));
+    assert_eq!(beap.try_pop_top(), Ok(1));
+    validate_heap_property(&beap);
+
+    *beap.try_peek_mut().unwrap() = 100;
+    validate_heap_property(&beap);
+    assert_eq!(beap.try_peek_largest(), Ok(&100));
+
+    // Errors can be displayed and converted from coordinate errors
+    let error: BeapError = BeapCoordinate::try_from((1, 2)).unwrap_err().into();
+    assert_eq!(
+        error,
+        BeapError::InvalidCoordinate(CoordinateError::PosOutOfRow { row: 1, pos: 2 })
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid coordinate: pos 2 is past the end of row 1"
+    );
+    assert_eq!(BeapError::Empty.to_string(), "the beap is empty");
+}
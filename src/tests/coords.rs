@@ -1,4 +1,6 @@
-use crate::beap::BeapCoordinate;
+use rand::Rng;
+
+use crate::beap::{BeapCoordinate, CoordinateError};
 
 fn coords(row: usize, pos: usize) -> BeapCoordinate {
     BeapCoordinate::from_coords(row, pos).unwrap()
@@ -52,3 +54,252 @@ fn test_invalid_step() {
     assert_eq!(coords(1, 1).left_child(), coords(2, 1));
     assert_eq!(coords(1, 1).right_child(), coords(2, 2));
 }
+
+// The original O(sqrt N) implementation, which subtracts row sizes until the index fits
+fn from_index_by_subtracting(index: usize) -> BeapCoordinate {
+    let mut row = 1;
+    let mut pos = index;
+    while pos >= row {
+        pos -= row;
+        row += 1;
+    }
+    coords(row - 1, pos)
+}
+
+// Checks the coordinate maps back to the index, using u128 so that huge rows can't overflow
+fn assert_round_trip(index: usize) {
+    let coord = BeapCoordinate::from_index(index);
+    let row = coord.row() as u128;
+    assert!(coord.pos() <= coord.row(), "index {}", index);
+    assert_eq!(row * (row + 1) / 2 + coord.pos() as u128, index as u128);
+}
+
+#[test]
+fn test_coord_from_index_small() {
+    for index in 0..100_000 {
+        assert_eq!(
+            BeapCoordinate::from_index(index),
+            from_index_by_subtracting(index)
+        );
+    }
+}
+
+#[test]
+fn test_coord_from_index_random() {
+    let mut rng = rand::thread_rng();
+
+    // Large enough to be far past the float precision edge cases, small enough to loop through
+    for _ in 0..200 {
+        let index = rng.gen_range(0..1usize << 36);
+        assert_eq!(
+            BeapCoordinate::from_index(index),
+            from_index_by_subtracting(index)
+        );
+    }
+
+    // Anywhere in the usize range
+    for _ in 0..100_000 {
+        assert_round_trip(rng.gen());
+    }
+}
+
+#[test]
+fn test_coord_from_index_huge() {
+    assert_round_trip(usize::MAX);
+    assert_round_trip(usize::MAX - 1);
+
+    // Both sides of the row boundaries near the end of the usize range, where float rounding
+    // is most likely to pick the wrong row
+    let max_row = BeapCoordinate::from_index(usize::MAX).row();
+    for row in max_row - 1000..=max_row {
+        let start = (row as u128 * (row as u128 + 1) / 2) as usize;
+        assert_eq!(BeapCoordinate::from_index(start), coords(row, 0));
+        assert_eq!(
+            BeapCoordinate::from_index(start - 1),
+            coords(row - 1, row - 1)
+        );
+        assert_round_trip(start + 1);
+    }
+}
+
+#[test]
+fn test_coord_max_row() {
+    let max_row = BeapCoordinate::MAX_ROW as u128;
+
+    // The last index of MAX_ROW fits in a usize, the last index of the row after it doesn't
+    let last = max_row * (max_row + 1) / 2 + max_row;
+    assert!(last <= usize::MAX as u128);
+    let next_last = last + 1 + (max_row + 1);
+    assert!(next_last > usize::MAX as u128);
+
+    let coord = coords(BeapCoordinate::MAX_ROW, BeapCoordinate::MAX_ROW);
+    assert_eq!(coord.checked_array_index(), Some(last as usize));
+    assert_eq!(coord.array_index(), last as usize);
+}
+
+#[test]
+fn test_coord_checked_array_index() {
+    for index in 0..1000 {
+        let coord = BeapCoordinate::from_index(index);
+        assert_eq!(coord.checked_array_index(), Some(index));
+    }
+
+    let past = BeapCoordinate::MAX_ROW + 1;
+    assert_eq!(coords(past, past).checked_array_index(), None);
+    assert_eq!(coords(usize::MAX, usize::MAX).checked_array_index(), None);
+    assert_eq!(coords(usize::MAX, 0).checked_array_index(), None);
+    assert_eq!(coords(usize::MAX - 1, 0).checked_array_index(), None);
+    assert_eq!(BeapCoordinate::checked_row_start_index(usize::MAX), None);
+}
+
+#[test]
+fn test_coord_checked_children() {
+    assert_eq!(coords(0, 0).checked_left_child(), Some(coords(1, 0)));
+    assert_eq!(coords(0, 0).checked_right_child(), Some(coords(1, 1)));
+
+    let below_max = coords(BeapCoordinate::MAX_ROW - 1, 5);
+    assert_eq!(below_max.checked_left_child(), Some(below_max.left_child()));
+    assert_eq!(
+        below_max.checked_right_child(),
+        Some(below_max.right_child())
+    );
+
+    let max = coords(BeapCoordinate::MAX_ROW, 5);
+    assert_eq!(max.checked_left_child(), None);
+    assert_eq!(max.checked_right_child(), None);
+    assert_eq!(coords(usize::MAX, 0).checked_left_child(), None);
+}
+
+#[test]
+fn test_coord_try_from() {
+    assert_eq!(BeapCoordinate::try_from(0), Ok(coords(0, 0)));
+    assert_eq!(BeapCoordinate::try_from(8), Ok(coords(3, 2)));
+    assert_eq!(BeapCoordinate::try_from((3, 2)), Ok(coords(3, 2)));
+    assert_eq!(
+        BeapCoordinate::try_from((2, 3)),
+        Err(CoordinateError::PosOutOfRow { row: 2, pos: 3 })
+    );
+
+    let max_row = BeapCoordinate::MAX_ROW;
+    assert_eq!(
+        BeapCoordinate::try_from((max_row, max_row)),
+        Ok(coords(max_row, max_row))
+    );
+    assert_eq!(
+        BeapCoordinate::try_from((max_row + 1, 0)),
+        Err(CoordinateError::RowTooLarge { row: max_row + 1 })
+    );
+
+    // The indexes after the last coordinate of MAX_ROW belong to a row that doesn't fit
+    let last = coords(max_row, max_row).array_index();
+    assert_eq!(BeapCoordinate::try_from(last), Ok(coords(max_row, max_row)));
+    assert_eq!(
+        BeapCoordinate::try_from(last + 1),
+        Err(CoordinateError::RowTooLarge { row: max_row + 1 })
+    );
+    assert_eq!(
+        BeapCoordinate::try_from(usize::MAX),
+        Err(CoordinateError::RowTooLarge { row: max_row + 1 })
+    );
+}
+
+#[test]
+fn test_coord_row_start_and_len() {
+    let mut index = 0;
+    for row in 0..100 {
+        assert_eq!(BeapCoordinate::row_start_index(row), index);
+        assert_eq!(BeapCoordinate::checked_row_start_index(row), Some(index));
+        assert_eq!(BeapCoordinate::from_index(index), coords(row, 0));
+        assert_eq!(BeapCoordinate::row_len(row), row + 1);
+        index += BeapCoordinate::row_len(row);
+    }
+}
+
+#[test]
+fn test_coord_next_in_order() {
+    let mut coord = BeapCoordinate::zero();
+    for index in 0..1000 {
+        assert_eq!(coord, BeapCoordinate::from_index(index));
+        coord = coord.next_in_order();
+    }
+}
+
+#[test]
+fn test_coord_siblings() {
+    let siblings = |coord: BeapCoordinate| coord.siblings().collect::<Vec<_>>();
+
+    assert_eq!(siblings(coords(0, 0)), vec![]);
+    assert_eq!(siblings(coords(1, 0)), vec![coords(1, 1)]);
+    assert_eq!(siblings(coords(1, 1)), vec![coords(1, 0)]);
+    assert_eq!(siblings(coords(3, 1)), vec![coords(3, 0), coords(3, 2)]);
+    assert_eq!(siblings(coords(3, 3)), vec![coords(3, 2)]);
+}
+
+// Every coordinate reachable by stepping through children, found by following them one at a time
+fn reachable_from(coord: BeapCoordinate, max_row: usize) -> Vec<BeapCoordinate> {
+    let mut reachable = vec![];
+    let mut frontier = vec![coord];
+    while let Some(next) = frontier.pop() {
+        if next.row() > max_row || reachable.contains(&next) {
+            continue;
+        }
+        reachable.push(next);
+        frontier.push(next.left_child());
+        frontier.push(next.right_child());
+    }
+    reachable
+}
+
+#[test]
+fn test_coord_ancestors() {
+    assert_eq!(coords(0, 0).ancestors().count(), 0);
+    assert_eq!(
+        coords(2, 1).ancestors().collect::<Vec<_>>(),
+        vec![coords(1, 0), coords(1, 1), coords(0, 0)]
+    );
+
+    for index in 0..100 {
+        let coord = BeapCoordinate::from_index(index);
+        let ancestors = coord.ancestors().collect::<Vec<_>>();
+
+        let expected = (0..index)
+            .map(BeapCoordinate::from_index)
+            .filter(|&other| reachable_from(other, coord.row()).contains(&coord));
+        assert_eq!(ancestors.len(), expected.clone().count());
+        for ancestor in expected {
+            assert!(ancestors.contains(&ancestor));
+        }
+
+        // Nearest rows first
+        assert!(ancestors.windows(2).all(|w| w[0].row() >= w[1].row()));
+    }
+}
+
+#[test]
+fn test_coord_descendants() {
+    assert_eq!(
+        coords(1, 1).descendants().take(5).collect::<Vec<_>>(),
+        vec![
+            coords(2, 1),
+            coords(2, 2),
+            coords(3, 1),
+            coords(3, 2),
+            coords(3, 3)
+        ]
+    );
+
+    for index in 0..50 {
+        let coord = BeapCoordinate::from_index(index);
+        let max_row = 12;
+
+        let descendants = coord
+            .descendants()
+            .take_while(|descendant| descendant.row() <= max_row)
+            .collect::<Vec<_>>();
+        let mut expected = reachable_from(coord, max_row);
+        expected.retain(|&other| other != coord);
+        expected.sort_by_key(|other| other.array_index());
+
+        assert_eq!(descendants, expected);
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rand::Rng;
+
+use super::beap::validate_min_layout;
+use crate::beap::{IndexedBeap, MaxOrder};
+
+#[test]
+fn test_handles_follow_elements() {
+    let mut beap = IndexedBeap::new();
+
+    let values = [1, 10, 5, 3, 15, 20, 2, 4, 6, 1, 10];
+    let handles: Vec<_> = values.iter().map(|&value| beap.insert(value)).collect();
+
+    // Every handle still points to its own value after all the swaps from inserting
+    for (handle, value) in handles.iter().zip(values.iter()) {
+        assert_eq!(beap.get(*handle), Some(value));
+        let coord = beap.coord(*handle).unwrap();
+        assert_eq!(beap.as_beap().get_coord(coord), Some(value));
+        assert_eq!(beap.handle_at(coord), Some(*handle));
+    }
+
+    // Decrease a key so that it moves to the top
+    assert_eq!(beap.update(handles[5], 0), Some(20));
+    assert_eq!(beap.peek_top(), Some((handles[5], &0)));
+
+    // Increase a key so that it sinks
+    assert_eq!(beap.update(handles[0], 30), Some(1));
+    assert_eq!(beap.get(handles[0]), Some(&30));
+
+    // Remove from the middle
+    assert_eq!(beap.remove(handles[2]), Some(5));
+    assert_eq!(beap.get(handles[2]), None);
+    assert!(!beap.contains(handles[2]));
+    assert_eq!(beap.remove(handles[2]), None);
+    assert_eq!(beap.update(handles[2], 5), None);
+
+    assert_eq!(beap.pop_top(), Some((handles[5], 0)));
+    assert!(!beap.contains(handles[5]));
+
+    // All the remaining handles are still correct
+    for &handle in handles.iter().filter(|&&handle| beap.contains(handle)) {
+        let coord = beap.coord(handle).unwrap();
+        assert_eq!(beap.handle_at(coord), Some(handle));
+        assert_eq!(beap.as_beap().get_coord(coord), beap.get(handle));
+    }
+
+    let mut popped = vec![];
+    while let Some((handle, value)) = beap.pop_top() {
+        assert!(!beap.contains(handle));
+        popped.push(value);
+    }
+    assert_eq!(popped, vec![1, 2, 3, 4, 6, 10, 10, 15, 30]);
+    assert!(beap.is_empty());
+}
+
+#[test]
+fn test_remove_last_element() {
+    let mut beap = IndexedBeap::new_by(MaxOrder);
+    let first = beap.insert(1);
+    let second = beap.insert(2);
+
+    assert_eq!(beap.remove(first), Some(1));
+    assert_eq!(beap.get(second), Some(&2));
+    assert_eq!(beap.remove(second), Some(2));
+    assert!(beap.is_empty());
+    assert_eq!(beap.peek_top(), None);
+}
+
+#[test]
+fn test_random_remove_by_handle() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..100 {
+        let mut beap = IndexedBeap::new();
+        let mut handles: Vec<_> = (0..rng.gen_range(1..80))
+            .map(|_| {
+                let value = rng.gen_range(0..50);
+                (beap.insert(value), value)
+            })
+            .collect();
+
+        // Every removal has to leave a valid beap with the other handles still in place
+        while !handles.is_empty() {
+            let (handle, value) = handles.swap_remove(rng.gen_range(0..handles.len()));
+            assert_eq!(beap.remove(handle), Some(value));
+            validate_min_layout(beap.as_beap().as_slice());
+            assert!(!beap.contains(handle));
+
+            for (handle, value) in &handles {
+                assert_eq!(beap.get(*handle), Some(value));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_handle_slots_are_reused() {
+    let mut beap = IndexedBeap::new();
+    let first = beap.insert(1);
+    assert_eq!(beap.remove(first), Some(1));
+
+    // The new element takes the freed slot, but the old handle stays removed
+    let second = beap.insert(2);
+    assert_ne!(first, second);
+    assert!(!beap.contains(first));
+    assert_eq!(beap.get(first), None);
+    assert_eq!(beap.remove(first), None);
+    assert_eq!(beap.update(first, 5), None);
+    assert_eq!(beap.get(second), Some(&2));
+
+    // A long running queue only needs as many slots as it holds elements at once
+    let mut rng = rand::thread_rng();
+    let mut handles = vec![second];
+    for round in 0..10_000 {
+        if handles.len() < 20 && (round % 3 != 0 || handles.is_empty()) {
+            handles.push(beap.insert(rng.gen_range(0..100)));
+        } else if round % 2 == 0 {
+            let handle = handles.swap_remove(rng.gen_range(0..handles.len()));
+            assert!(beap.remove(handle).is_some());
+        } else {
+            let (handle, _) = beap.pop_top().unwrap();
+            handles.retain(|&other| other != handle);
+        }
+
+        assert_eq!(beap.len(), handles.len());
+        for &handle in &handles {
+            assert!(beap.contains(handle));
+        }
+    }
+    assert!(beap.slot_count() <= 20);
+}
+
+#[test]
+fn test_handles_survive_panicking_comparison() {
+    // Comparing against 99 panics while the flag is set
+    let panicking = Cell::new(false);
+    let mut beap = IndexedBeap::new_by(|a: &i32, b: &i32| {
+        if panicking.get() && (*a == 99 || *b == 99) {
+            panic!("comparison failed");
+        }
+        a.cmp(b)
+    });
+    let handles: Vec<_> = [5, 1, 3].iter().map(|&value| beap.insert(value)).collect();
+
+    // 99 is larger than everything, so the beap stays valid where the panic leaves it
+    panicking.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| beap.insert(99)));
+    assert!(result.is_err());
+    panicking.set(false);
+
+    // The handles still point to their elements, and the beap keeps working
+    for (handle, value) in handles.iter().zip([5, 1, 3].iter()) {
+        assert!(beap.contains(*handle));
+        assert_eq!(beap.get(*handle), Some(value));
+    }
+    let handle = beap.insert(0);
+    assert_eq!(beap.peek_top(), Some((handle, &0)));
+
+    let mut popped = vec![];
+    while let Some((_, value)) = beap.pop_top() {
+        popped.push(value);
+    }
+    assert_eq!(popped, vec![0, 1, 3, 5, 99]);
+}
+
+#[test]
+fn test_dijkstra_decrease_key() {
+    // (from, to, weight)
+    let edges = [
+        (0, 1, 7),
+        (0, 2, 9),
+        (0, 5, 14),
+        (1, 2, 10),
+        (1, 3, 15),
+        (2, 3, 11),
+        (2, 5, 2),
+        (3, 4, 6),
+        (4, 5, 9),
+    ];
+    let node_count = 6;
+
+    // Each node is queued once, keyed by (distance, node)
+    let mut queue = IndexedBeap::new();
+    let mut distances = vec![u32::MAX; node_count];
+    distances[0] = 0;
+    let handles: Vec<_> = (0..node_count)
+        .map(|node| queue.insert((distances[node], node)))
+        .collect();
+
+    while let Some((_, (distance, node))) = queue.pop_top() {
+        for &(a, b, weight) in edges.iter() {
+            let other = if a == node {
+                b
+            } else if b == node {
+                a
+            } else {
+                continue;
+            };
+
+            let new_distance = distance + weight;
+            if queue.contains(handles[other]) && new_distance < distances[other] {
+                distances[other] = new_distance;
+                queue.update(handles[other], (new_distance, other));
+            }
+        }
+    }
+
+    assert_eq!(distances, vec![0, 7, 9, 20, 20, 11]);
+}